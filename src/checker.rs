@@ -0,0 +1,728 @@
+use std::{collections::HashMap, fmt::Display, rc::Rc};
+
+use crate::{error::report, expr::{Expr, ExprType}, function::{arity_error, max_arity, min_arity}, interpreter::PRELUDE, native::NativeFunction, object::Object, parser::parse,
+    scanner::scan_tokens, stmt::{Destructure, Parameter, Pattern, Stmt, TypeAnnotation}, token::{Token, TokenType}};
+
+/// Checks the types of a resolved program and reports every mismatch it finds.
+/// Returns the number of reported errors.
+pub fn check(statements: &[Stmt]) -> usize {
+    let mut checker = Checker::new();
+    checker.declare_all(&parse(scan_tokens(PRELUDE.to_string())));
+    checker.declare_all(statements);
+    for statement in statements {
+        checker.visit_stmt(statement);
+    }
+    checker.errors
+}
+
+/// The static type of a value. `Any` is the type of everything the checker
+/// knows nothing about, such as unannotated parameters, and matches all types.
+#[derive(Clone, PartialEq)]
+enum Type {
+    Any,
+    Number,
+    String,
+    Bool,
+    Nil,
+    List,
+    Map,
+    Range,
+    Function(Option<Rc<Signature>>),
+    Class(String),
+    Instance(String),
+    Nullable(Box<Type>),
+}
+
+impl Display for Type {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Type::Any => write!(f, "any"),
+            Type::Number => write!(f, "number"),
+            Type::String => write!(f, "string"),
+            Type::Bool => write!(f, "bool"),
+            Type::Nil => write!(f, "nil"),
+            Type::List => write!(f, "list"),
+            Type::Map => write!(f, "map"),
+            Type::Range => write!(f, "range"),
+            Type::Function(_) => write!(f, "function"),
+            Type::Class(name) => write!(f, "class {name}"),
+            Type::Instance(name) => write!(f, "{name}"),
+            Type::Nullable(inner) => write!(f, "{inner}?"),
+        }
+    }
+}
+
+/// Parameter and return types of a function or method.
+#[derive(PartialEq)]
+struct Signature {
+    name: String,
+    parameters: Vec<Type>,
+    min_arity: usize,
+    max_arity: Option<usize>,
+    returns: Type,
+}
+
+/// What the checker knows about a class: its superclass and the types of its
+/// methods, getters and declared fields.
+#[derive(Default)]
+struct ClassInfo {
+    superclass: Option<String>,
+    methods: HashMap<String, Rc<Signature>>,
+    properties: HashMap<String, Type>,
+}
+
+struct Checker {
+    scopes: Vec<HashMap<String, Type>>,
+    classes: HashMap<String, ClassInfo>,
+    current_class: Option<String>,
+    // Declared return type of the function being checked, if any.
+    current_return: Option<Type>,
+    errors: usize,
+}
+
+impl Checker {
+
+    fn new() -> Self {
+        let mut globals = HashMap::new();
+        for native in NativeFunction::ALL {
+            let returns = match native {
+                NativeFunction::Clock => Type::Number,
+                NativeFunction::Input | NativeFunction::ReadFile => Type::String,
+                NativeFunction::Map => Type::Map,
+                NativeFunction::Methods | NativeFunction::Fields => Type::List,
+                NativeFunction::Fiber => Type::Any
+            };
+            let signature = Signature {
+                name: native.to_string(),
+                parameters: vec![Type::Any; native.arity()],
+                min_arity: native.arity(),
+                max_arity: Some(native.arity()),
+                returns
+            };
+            globals.insert(native.to_string(), Type::Function(Some(Rc::new(signature))));
+        }
+
+        Checker {
+            scopes: vec![globals],
+            classes: HashMap::new(),
+            current_class: None,
+            current_return: None,
+            errors: 0,
+        }
+    }
+
+    fn error(&mut self, token: &Token, message: String) {
+        report(token.line, format!("at {}", token.lexeme), message);
+        self.errors += 1;
+    }
+
+    /// Declares the top-level functions and classes up front, so function
+    /// bodies can refer to declarations further down.
+    fn declare_all(&mut self, statements: &[Stmt]) {
+        for statement in statements {
+            let statement = match statement {
+                Stmt::Export(declaration) => declaration.as_ref(),
+                statement => statement
+            };
+            match statement {
+                Stmt::Function(name, parameters, _body, generator, returns) => {
+                    let signature = self.signature(name, parameters, returns, *generator);
+                    self.define(name, Type::Function(Some(signature)));
+                },
+                Stmt::Class(name, superclass, _traits, methods, getters, _setters, fields, abstracts) => {
+                    self.define(name, Type::Class(name.lexeme.clone()));
+                    self.declare_class(name, superclass, methods, getters, fields, abstracts);
+                },
+                _ => {}
+            }
+        }
+    }
+
+    fn declare_class(&mut self, name: &Token, superclass: &Option<Expr>, methods: &[Stmt], getters: &[Stmt], fields: &[Stmt], abstracts: &[Stmt]) {
+        // Registered first, so members can use the class in their annotations.
+        self.classes.entry(name.lexeme.clone()).or_default();
+
+        let mut info = ClassInfo {
+            superclass: match superclass {
+                Some(Expr { expr_type: ExprType::Variable(superclass), .. }) => Some(superclass.lexeme.clone()),
+                _ => None
+            },
+            ..ClassInfo::default()
+        };
+        for method in methods.iter().chain(abstracts) {
+            if let Stmt::Function(method, parameters, _body, generator, returns) = method {
+                let signature = self.signature(method, parameters, returns, *generator);
+                info.methods.insert(method.lexeme.clone(), signature);
+            }
+        }
+        for getter in getters {
+            if let Stmt::Function(getter, _, _, _, returns) = getter {
+                info.properties.insert(getter.lexeme.clone(), self.annotation(returns));
+            }
+        }
+        for field in fields {
+            if let Stmt::Var(field, _, annotation) = field {
+                info.properties.insert(field.lexeme.clone(), self.annotation(annotation));
+            }
+        }
+        self.classes.insert(name.lexeme.clone(), info);
+    }
+
+    fn signature(&mut self, name: &Token, parameters: &[Parameter], returns: &Option<TypeAnnotation>, generator: bool) -> Rc<Signature> {
+        let fixed: Vec<&Parameter> = parameters.iter().filter(|parameter| !parameter.rest).collect();
+        let returns = if generator { Type::Any } else { self.annotation(returns) };
+        Rc::new(Signature {
+            name: name.lexeme.clone(),
+            parameters: fixed.iter().map(|parameter| self.annotation(&parameter.annotation)).collect(),
+            min_arity: min_arity(parameters),
+            max_arity: max_arity(parameters),
+            returns
+        })
+    }
+
+    fn annotation(&mut self, annotation: &Option<TypeAnnotation>) -> Type {
+        let annotation = match annotation {
+            Some(annotation) => annotation,
+            None => return Type::Any
+        };
+
+        let annotated = match annotation.name.lexeme.as_str() {
+            "any" => return Type::Any,
+            "number" => Type::Number,
+            "string" => Type::String,
+            "bool" => Type::Bool,
+            "nil" => return Type::Nil,
+            "list" => Type::List,
+            "map" => Type::Map,
+            "range" => Type::Range,
+            "function" => Type::Function(None),
+            name if self.classes.contains_key(name) => Type::Instance(name.to_string()),
+            name => {
+                self.error(&annotation.name, format!("Unknown type '{}'.", name));
+                return Type::Any;
+            }
+        };
+
+        if annotation.nullable { Type::Nullable(Box::new(annotated)) } else { annotated }
+    }
+
+    fn begin_scope(&mut self) {
+        self.scopes.push(HashMap::new());
+    }
+
+    fn end_scope(&mut self) {
+        self.scopes.pop();
+    }
+
+    fn define(&mut self, name: &Token, value: Type) {
+        self.scopes.last_mut().unwrap().insert(name.lexeme.clone(), value);
+    }
+
+    fn lookup(&self, name: &Token) -> Type {
+        self.scopes.iter().rev()
+            .find_map(|scope| scope.get(&name.lexeme))
+            .cloned()
+            .unwrap_or(Type::Any)
+    }
+
+    fn is_subclass(&self, class: &str, superclass: &str) -> bool {
+        let mut current = Some(class.to_string());
+        while let Some(class) = current {
+            if class == superclass {
+                return true;
+            }
+            current = self.classes.get(&class).and_then(|info| info.superclass.clone());
+        }
+        false
+    }
+
+    fn assignable(&self, from: &Type, to: &Type) -> bool {
+        match (from, to) {
+            (Type::Any, _) | (_, Type::Any) => true,
+            (Type::Nil, Type::Nullable(_)) => true,
+            (Type::Nullable(from), Type::Nullable(to)) => self.assignable(from, to),
+            (from, Type::Nullable(to)) => self.assignable(from, to),
+            (Type::Function(_) | Type::Class(_), Type::Function(None)) => true,
+            (Type::Function(_), Type::Function(_)) => true,
+            (Type::Instance(from), Type::Instance(to)) => self.is_subclass(from, to),
+            (from, to) => from == to
+        }
+    }
+
+    /// The type of both branches if they agree, and `any` otherwise.
+    fn join(&self, left: Type, right: Type) -> Type {
+        match (left, right) {
+            (left, right) if left == right => left,
+            (Type::Nil, other) | (other, Type::Nil) => match other {
+                Type::Nullable(_) | Type::Any => other,
+                other => Type::Nullable(Box::new(other))
+            },
+            _ => Type::Any
+        }
+    }
+
+    fn check_assignment(&mut self, value: &Type, target: &Type, token: &Token, what: &str) {
+        if !self.assignable(value, target) {
+            self.error(token, format!("Can't assign {} to {} of type {}.", value, what, target));
+        }
+    }
+
+    fn method(&self, class: &str, name: &str) -> Option<Rc<Signature>> {
+        let info = self.classes.get(class)?;
+        match info.methods.get(name) {
+            Some(method) => Some(method.clone()),
+            None => self.method(info.superclass.as_ref()?, name)
+        }
+    }
+
+    fn property(&self, class: &str, name: &str) -> Option<Type> {
+        let info = self.classes.get(class)?;
+        match info.properties.get(name) {
+            Some(property) => Some(property.clone()),
+            None => self.property(info.superclass.as_ref()?, name)
+        }
+    }
+
+    /// Type of `object.name`. Fields assigned in methods aren't declared
+    /// anywhere, so unknown properties of instances are `any`.
+    fn get(&mut self, object: &Type, name: &Token) -> Type {
+        match object {
+            Type::Instance(class) => {
+                if let Some(property) = self.property(class, &name.lexeme) {
+                    return property;
+                }
+                match self.method(class, &name.lexeme) {
+                    Some(method) => Type::Function(Some(method)),
+                    None => Type::Any
+                }
+            },
+            Type::List | Type::Range if name.lexeme == "length" => Type::Number,
+            Type::Map if name.lexeme == "size" => Type::Number,
+            Type::Number | Type::Bool | Type::Nil | Type::String => {
+                self.error(name, format!("Only instances have properties, got {}.", object));
+                Type::Any
+            },
+            _ => Type::Any
+        }
+    }
+
+    fn call(&mut self, callee: &Type, arguments: &[Type], paren: &Token) -> Type {
+        let (signature, returns) = match callee {
+            Type::Function(Some(signature)) => (Some(signature.clone()), signature.returns.clone()),
+            Type::Class(class) => (self.method(class, "init"), Type::Instance(class.clone())),
+            Type::Number | Type::String | Type::Bool | Type::Nil | Type::List | Type::Map | Type::Range => {
+                self.error(paren, format!("Can only call functions and classes, got {}.", callee));
+                return Type::Any;
+            },
+            _ => return Type::Any
+        };
+
+        if let Some(signature) = signature {
+            if let Some(message) = arity_error(signature.min_arity, signature.max_arity, arguments.len()) {
+                self.error(paren, message);
+            }
+            for (i, (argument, parameter)) in arguments.iter().zip(signature.parameters.iter()).enumerate() {
+                if !self.assignable(argument, parameter) {
+                    self.error(paren, format!("Argument {} of '{}' must be {}, got {}.", i + 1, signature.name, parameter, argument));
+                }
+            }
+        }
+        returns
+    }
+
+    fn binary(&mut self, left: &Type, op: &Token, right: &Type) -> Type {
+        match op.token_type {
+            TokenType::EqualEqual | TokenType::BangEqual | TokenType::Is => return Type::Bool,
+            _ => {}
+        }
+
+        // Instances may overload operators, so nothing is known about the result.
+        if matches!(left, Type::Any | Type::Instance(_)) || matches!(right, Type::Any | Type::Instance(_)) {
+            return Type::Any;
+        }
+
+        let numbers = *left == Type::Number && *right == Type::Number;
+        match op.token_type {
+            TokenType::Plus if numbers => Type::Number,
+            TokenType::Plus if *left == Type::String || *right == Type::String => Type::String,
+            TokenType::Plus => {
+                self.error(op, format!("Operands of '+' must be two numbers or include a string, got {} and {}.", left, right));
+                Type::Any
+            },
+            TokenType::Greater | TokenType::GreaterEqual | TokenType::Less | TokenType::LessEqual if numbers => Type::Bool,
+            _ if numbers => Type::Number,
+            _ => {
+                self.error(op, format!("Operands of '{}' must be numbers, got {} and {}.", op.lexeme, left, right));
+                Type::Any
+            }
+        }
+    }
+
+    fn check_function(&mut self, parameters: &[Parameter], body: &[Stmt], returns: Type) {
+        let enclosing_return = self.current_return.replace(returns);
+        self.begin_scope();
+        for parameter in parameters {
+            let annotated = self.annotation(&parameter.annotation);
+            if let Some(default) = &parameter.default {
+                let default = self.visit_expr(default);
+                self.check_assignment(&default, &annotated, &parameter.name, "parameter");
+            }
+            let parameter_type = if parameter.rest { Type::List } else { annotated };
+            self.define(&parameter.name, parameter_type);
+        }
+        for statement in body {
+            self.visit_stmt(statement);
+        }
+        self.end_scope();
+        self.current_return = enclosing_return;
+    }
+
+    fn check_methods(&mut self, methods: &[Stmt]) {
+        for method in methods {
+            if let Stmt::Function(_, parameters, body, generator, returns) = method {
+                let returns = if *generator { Type::Any } else { self.annotation(returns) };
+                self.check_function(parameters, body, returns);
+            }
+        }
+    }
+
+    fn pattern(&mut self, pattern: &Pattern, bindings: &mut Vec<(Token, Type)>) {
+        match pattern {
+            Pattern::Wildcard => {},
+            Pattern::Binding(name) => bindings.push((name.clone(), Type::Any)),
+            Pattern::Value(value) => {
+                self.visit_expr(value);
+            },
+            Pattern::Class(class, name) => {
+                let bound = match self.visit_expr(class) {
+                    Type::Class(class) => Type::Instance(class),
+                    _ => Type::Any
+                };
+                bindings.push((name.clone(), bound));
+            },
+            Pattern::List(elements, rest) => {
+                for element in elements {
+                    self.pattern(element, bindings);
+                }
+                if let Some(rest) = rest {
+                    bindings.push((rest.clone(), Type::List));
+                }
+            }
+        }
+    }
+
+    fn visit_stmt(&mut self, stmt: &Stmt) {
+        match stmt {
+            Stmt::Assign(targets, _equals, values) => {
+                let values: Vec<Type> = values.iter().map(|value| self.visit_expr(value)).collect();
+                for (i, target) in targets.iter().enumerate() {
+                    let value = if values.len() == targets.len() { values[i].clone() } else { Type::Any };
+                    match &target.expr_type {
+                        ExprType::Variable(name) => {
+                            let variable = self.lookup(name);
+                            self.check_assignment(&value, &variable, name, &format!("'{}'", name.lexeme));
+                        },
+                        _ => {
+                            self.visit_expr(target);
+                        }
+                    }
+                }
+            },
+            Stmt::Block(statements) => {
+                self.begin_scope();
+                for statement in statements {
+                    self.visit_stmt(statement);
+                }
+                self.end_scope();
+            },
+            Stmt::Class(name, superclass, traits, methods, getters, setters, fields, abstracts) => {
+                if let Some(superclass) = superclass.as_ref() {
+                    self.visit_expr(superclass);
+                }
+                for used in traits {
+                    self.visit_expr(used);
+                }
+                self.define(name, Type::Class(name.lexeme.clone()));
+                self.declare_class(name, superclass, methods, getters, fields, abstracts);
+
+                let enclosing_class = self.current_class.replace(name.lexeme.clone());
+                for field in fields {
+                    if let Stmt::Var(field, default, annotation) = field {
+                        let annotated = self.annotation(annotation);
+                        let default = self.visit_expr(default);
+                        self.check_assignment(&default, &annotated, field, &format!("field '{}'", field.lexeme));
+                    }
+                }
+                self.check_methods(methods);
+                self.check_methods(getters);
+                self.check_methods(setters);
+                self.current_class = enclosing_class;
+            },
+            Stmt::Const(name, initializer, annotation) | Stmt::Var(name, initializer, annotation) => {
+                let value = self.visit_expr(initializer);
+                let declared = match annotation {
+                    Some(_) => {
+                        let annotated = self.annotation(annotation);
+                        self.check_assignment(&value, &annotated, name, &format!("'{}'", name.lexeme));
+                        annotated
+                    },
+                    // A constant keeps its initializer's type, an unannotated
+                    // variable may be assigned anything later on.
+                    None if matches!(stmt, Stmt::Const(_, _, _)) => value,
+                    None => Type::Any
+                };
+                self.define(name, declared);
+            },
+            Stmt::VarDestructure(destructure, initializer) => {
+                self.visit_expr(initializer);
+                let names = match destructure.as_ref() {
+                    Destructure::List(names, rest) => names.iter().chain(rest.iter()).collect::<Vec<&Token>>(),
+                    Destructure::Fields(names) => names.iter().collect()
+                };
+                for name in names {
+                    self.define(name, Type::Any);
+                }
+            },
+            Stmt::Enum(name, _members) => self.define(name, Type::Any),
+            Stmt::Export(declaration) => self.visit_stmt(declaration),
+            Stmt::Expression(expr) | Stmt::Print(_, expr) | Stmt::Throw(_, expr) | Stmt::Yield(_, expr) => {
+                self.visit_expr(expr);
+            },
+            Stmt::ForIn(name, iterable, body) => {
+                let element = match self.visit_expr(iterable) {
+                    Type::Range => Type::Number,
+                    Type::String => Type::String,
+                    iterable @ (Type::Number | Type::Bool | Type::Nil | Type::Function(_)) => {
+                        self.error(name, format!("Can't iterate over {}.", iterable));
+                        Type::Any
+                    },
+                    _ => Type::Any
+                };
+                self.begin_scope();
+                self.define(name, element);
+                self.visit_stmt(body);
+                self.end_scope();
+            },
+            Stmt::Function(name, parameters, body, generator, returns) => {
+                let signature = self.signature(name, parameters, returns, *generator);
+                self.define(name, Type::Function(Some(signature.clone())));
+                let enclosing_class = self.current_class.take();
+                self.check_function(parameters, body, signature.returns.clone());
+                self.current_class = enclosing_class;
+            },
+            Stmt::If(condition, then_branch, else_branch) => {
+                self.visit_expr(condition);
+                self.visit_stmt(then_branch);
+                if let Some(else_branch) = else_branch.as_ref() {
+                    self.visit_stmt(else_branch);
+                }
+            },
+            Stmt::Import(_path, names) => {
+                for name in names.iter().flatten() {
+                    self.define(name, Type::Any);
+                }
+            },
+            Stmt::Match(_keyword, subject, cases) => {
+                self.visit_expr(subject);
+                for case in cases {
+                    let mut bindings = Vec::new();
+                    for pattern in &case.patterns {
+                        self.pattern(pattern, &mut bindings);
+                    }
+                    self.begin_scope();
+                    for (name, bound) in bindings {
+                        // A name bound by several alternatives may hold different types.
+                        let bound = match self.scopes.last().unwrap().get(&name.lexeme) {
+                            Some(previous) if *previous != bound => Type::Any,
+                            _ => bound
+                        };
+                        self.define(&name, bound);
+                    }
+                    if let Some(guard) = &case.guard {
+                        self.visit_expr(guard);
+                    }
+                    self.visit_stmt(&case.body);
+                    self.end_scope();
+                }
+            },
+            Stmt::Return(keyword, value) => {
+                let value = match value.as_ref() {
+                    Some(value) => self.visit_expr(value),
+                    None => Type::Nil
+                };
+                if let Some(returns) = self.current_return.clone() {
+                    if !self.assignable(&value, &returns) {
+                        self.error(keyword, format!("Can't return {} from a function returning {}.", value, returns));
+                    }
+                }
+            },
+            Stmt::Trait(_name, methods, getters, setters) => {
+                let enclosing_class = self.current_class.take();
+                self.check_methods(methods);
+                self.check_methods(getters);
+                self.check_methods(setters);
+                self.current_class = enclosing_class;
+            },
+            Stmt::Try(body, catch, finally) => {
+                for block in std::iter::once(body).chain(catch.iter().map(|(_, block)| block)).chain(finally.iter()) {
+                    self.begin_scope();
+                    if let Some((name, catch_block)) = catch.as_ref() {
+                        if std::ptr::eq(block, catch_block) {
+                            self.define(name, Type::Any);
+                        }
+                    }
+                    for statement in block {
+                        self.visit_stmt(statement);
+                    }
+                    self.end_scope();
+                }
+            },
+            Stmt::While(_keyword, condition, body) => {
+                self.visit_expr(condition);
+                self.visit_stmt(body);
+            },
+        }
+    }
+
+    fn visit_expr(&mut self, expr: &Expr) -> Type {
+        match &expr.expr_type {
+            ExprType::Assign(name, value) => {
+                let value = self.visit_expr(value);
+                let variable = self.lookup(name);
+                self.check_assignment(&value, &variable, name, &format!("'{}'", name.lexeme));
+                value
+            },
+            ExprType::Binary(left, op, right) => {
+                let left = self.visit_expr(left);
+                let right = self.visit_expr(right);
+                self.binary(&left, op, &right)
+            },
+            ExprType::Call(callee, paren, arguments) => {
+                let callee = self.visit_expr(callee);
+                let arguments: Vec<Type> = arguments.iter().map(|argument| self.visit_expr(argument)).collect();
+                self.call(&callee, &arguments, paren)
+            },
+            ExprType::CompoundAssign(name, op, value, postfix) => {
+                let current = self.lookup(name);
+                let operand = self.visit_expr(value);
+                let result = self.binary(&current, op, &operand);
+                self.check_assignment(&result, &current, name, &format!("'{}'", name.lexeme));
+                if *postfix { current } else { result }
+            },
+            ExprType::CompoundSet(object, name, op, value, postfix) => {
+                let object = self.visit_expr(object);
+                let current = self.get(&object, name);
+                let operand = self.visit_expr(value);
+                let result = self.binary(&current, op, &operand);
+                self.check_assignment(&result, &current, name, &format!("property '{}'", name.lexeme));
+                if *postfix { current } else { result }
+            },
+            ExprType::CompoundSetIndex(object, _bracket, index, _op, value, _postfix) => {
+                self.visit_expr(object);
+                self.visit_expr(index);
+                self.visit_expr(value);
+                Type::Any
+            },
+            ExprType::Conditional(condition, then_branch, else_branch) => {
+                self.visit_expr(condition);
+                let then_branch = self.visit_expr(then_branch);
+                let else_branch = self.visit_expr(else_branch);
+                self.join(then_branch, else_branch)
+            },
+            ExprType::Get(object, name) => {
+                let object = self.visit_expr(object);
+                self.get(&object, name)
+            },
+            ExprType::Grouping(expr) => self.visit_expr(expr),
+            ExprType::Index(object, bracket, index) => {
+                let object = self.visit_expr(object);
+                let index = self.visit_expr(index);
+                match (object, index) {
+                    (Type::String, _) => Type::String,
+                    (Type::List, Type::Range) => Type::List,
+                    (object @ (Type::Number | Type::Bool | Type::Nil | Type::Function(_) | Type::Class(_)), _) => {
+                        self.error(bracket, format!("Only instances, lists, maps and strings can be indexed, got {}.", object));
+                        Type::Any
+                    },
+                    _ => Type::Any
+                }
+            },
+            ExprType::List(elements) => {
+                for element in elements {
+                    self.visit_expr(element);
+                }
+                Type::List
+            },
+            ExprType::Literal(value) => match value {
+                Object::Number(_) => Type::Number,
+                Object::String(_) => Type::String,
+                Object::Boolean(_) => Type::Bool,
+                Object::Nil => Type::Nil,
+                _ => Type::Any
+            },
+            ExprType::Logical(left, op, right) => {
+                let left = self.visit_expr(left);
+                let right = self.visit_expr(right);
+                match (op.token_type.clone(), left) {
+                    (TokenType::QuestionQuestion, Type::Nullable(left)) => self.join(*left, right),
+                    (TokenType::QuestionQuestion, Type::Nil) => right,
+                    (_, left) => self.join(left, right)
+                }
+            },
+            ExprType::OptionalChain(expr) => match self.visit_expr(expr) {
+                Type::Any => Type::Any,
+                value => self.join(value, Type::Nil)
+            },
+            ExprType::OptionalGet(object, name) => match self.visit_expr(object) {
+                Type::Nullable(object) => self.get(&object, name),
+                Type::Nil => Type::Nil,
+                object => self.get(&object, name)
+            },
+            ExprType::Range(start, operator, end, step) => {
+                let mut bounds = vec![self.visit_expr(start), self.visit_expr(end)];
+                if let Some(step) = step {
+                    bounds.push(self.visit_expr(step));
+                }
+                if bounds.iter().any(|bound| !self.assignable(bound, &Type::Number)) {
+                    self.error(operator, String::from("Range bounds and step must be numbers."));
+                }
+                Type::Range
+            },
+            ExprType::Set(object, name, value) => {
+                let object = self.visit_expr(object);
+                let value = self.visit_expr(value);
+                if let Type::Instance(class) = &object {
+                    if let Some(property) = self.property(class, &name.lexeme) {
+                        self.check_assignment(&value, &property, name, &format!("property '{}'", name.lexeme));
+                    }
+                }
+                value
+            },
+            ExprType::SetIndex(object, _bracket, index, value) => {
+                self.visit_expr(object);
+                self.visit_expr(index);
+                self.visit_expr(value)
+            },
+            ExprType::Super(_keyword, _method) => Type::Any,
+            ExprType::This(_keyword) => match &self.current_class {
+                Some(class) => Type::Instance(class.clone()),
+                None => Type::Any
+            },
+            ExprType::Unary(op, right) => {
+                let right = self.visit_expr(right);
+                match op.token_type {
+                    TokenType::Bang => Type::Bool,
+                    TokenType::TypeOf => Type::String,
+                    _ if matches!(right, Type::Any | Type::Instance(_)) => Type::Any,
+                    _ if right == Type::Number => Type::Number,
+                    _ => {
+                        self.error(op, format!("Operand of '{}' must be a number, got {}.", op.lexeme, right));
+                        Type::Any
+                    }
+                }
+            },
+            ExprType::Variable(name) => self.lookup(name),
+        }
+    }
+
+}
@@ -1,41 +1,124 @@
 use std::{cell::RefCell, collections::HashMap, fmt::Display, rc::Rc};
 
-use crate::object::Object;
+use crate::{environment::Environment, expr::Expr, object::Object};
 
+/// A field declared in a class body. Its default is evaluated for every new
+/// instance, with `this` bound to it, before `init` runs.
+#[derive(Clone)]
+pub struct Field {
+    pub name: String,
+    pub default: Expr,
+    pub closure: Rc<RefCell<Environment>>,
+}
 
 #[derive(Clone)]
 pub struct Class {
     pub name: String,
     superclass: Option<Rc<RefCell<Class>>>,
+    traits: Vec<Rc<Trait>>,
     methods: HashMap<String, Object>,
+    getters: HashMap<String, Object>,
+    setters: HashMap<String, Object>,
+    pub fields: Vec<Field>,
+    // Abstract methods declared here or inherited and not implemented yet.
+    pub abstracts: Vec<String>,
 }
 
 impl Class {
 
-    pub fn new(name: String, superclass: Option<Rc<RefCell<Class>>>, methods: HashMap<String, Object>) -> Self {
+    pub fn new(name: String, superclass: Option<Rc<RefCell<Class>>>, traits: Vec<Rc<Trait>>, methods: HashMap<String, Object>,
+        getters: HashMap<String, Object>, setters: HashMap<String, Object>) -> Self {
         Class {
-            name, 
+            name,
             superclass,
-            methods
+            traits,
+            methods,
+            getters,
+            setters,
+            fields: Vec::new(),
+            abstracts: Vec::new()
         }
     }
 
+    pub fn superclass(&self) -> Option<Rc<RefCell<Class>>> {
+        self.superclass.clone()
+    }
+
     pub fn find_method(&self, name: &str) -> Option<Object> {
-        if let Some(method) = self.methods.get(name).cloned() {
-            return Some(method)
+        self.find_member(name, |class| &class.methods)
+    }
+
+    pub fn find_getter(&self, name: &str) -> Option<Object> {
+        self.find_member(name, |class| &class.getters)
+    }
+
+    pub fn find_setter(&self, name: &str) -> Option<Object> {
+        self.find_member(name, |class| &class.setters)
+    }
+
+    /// Looks a member up in this class and then along its superclasses.
+    fn find_member(&self, name: &str, members: fn(&Class) -> &HashMap<String, Object>) -> Option<Object> {
+        match members(self).get(name) {
+            Some(member) => Some(member.clone()),
+            None => self.superclass.as_ref()?.borrow().find_member(name, members)
+        }
+    }
+
+    pub fn is_subclass_of(&self, class: &Rc<RefCell<Class>>) -> bool {
+        if std::ptr::eq(self, class.as_ptr()) {
+            return true;
+        }
+
+        match self.superclass.as_ref() {
+            Some(superclass) => superclass.borrow().is_subclass_of(class),
+            None => false
+        }
+    }
+
+    pub fn uses_trait(&self, used: &Rc<Trait>) -> bool {
+        if self.traits.iter().any(|t| Rc::ptr_eq(t, used)) {
+            return true;
         }
 
-        if let Some(superclass) = self.superclass.clone() {
-            return superclass.borrow().find_method(name);
+        match self.superclass.as_ref() {
+            Some(superclass) => superclass.borrow().uses_trait(used),
+            None => false
         }
+    }
 
-        return None;
+    /// Names of all methods of the class, including inherited ones.
+    pub fn method_names(&self) -> Vec<String> {
+        let mut names = match self.superclass.as_ref() {
+            Some(superclass) => superclass.borrow().method_names(),
+            None => Vec::new()
+        };
+        for name in self.methods.keys() {
+            if !names.contains(name) {
+                names.push(name.clone());
+            }
+        }
+        names.sort();
+        names
     }
 
 }
 
+/// A named set of methods, getters and setters that classes include with `with`.
+pub struct Trait {
+    pub name: String,
+    pub methods: HashMap<String, Object>,
+    pub getters: HashMap<String, Object>,
+    pub setters: HashMap<String, Object>,
+}
+
+impl Display for Trait {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name)
+    }
+}
+
 impl Display for Class {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}", self.name)
     }
-}
\ No newline at end of file
+}
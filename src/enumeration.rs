@@ -0,0 +1,60 @@
+use std::{fmt::Display, rc::{Rc, Weak}};
+
+/// An enum declared with `enum Name { A, B, C }`. Its members are created
+/// once, in declaration order, and compared by identity.
+pub struct Enum {
+    pub name: String,
+    pub members: Vec<Rc<Member>>,
+}
+
+impl Enum {
+
+    pub fn new(name: String, members: Vec<String>) -> Rc<Self> {
+        Rc::new_cyclic(|owner| Enum {
+            members: members.into_iter().enumerate().map(|(ordinal, member)| Rc::new(Member {
+                name: member,
+                ordinal,
+                enumeration: name.clone(),
+                owner: owner.clone()
+            })).collect(),
+            name
+        })
+    }
+
+    pub fn member(&self, name: &str) -> Option<Rc<Member>> {
+        self.members.iter().find(|member| member.name == name).cloned()
+    }
+
+    pub fn contains(&self, member: &Member) -> bool {
+        std::ptr::eq(member.owner.as_ptr(), self)
+    }
+
+}
+
+impl Display for Enum {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name)
+    }
+}
+
+/// A member of an enum with its name and its position in the declaration.
+pub struct Member {
+    pub name: String,
+    pub ordinal: usize,
+    enumeration: String,
+    owner: Weak<Enum>,
+}
+
+impl Member {
+
+    pub fn same_enum(&self, other: &Member) -> bool {
+        self.owner.ptr_eq(&other.owner)
+    }
+
+}
+
+impl Display for Member {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}.{}", self.enumeration, self.name)
+    }
+}
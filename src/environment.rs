@@ -1,11 +1,12 @@
-use std::{cell::RefCell, collections::HashMap, rc::Rc};
+use std::{cell::RefCell, collections::{HashMap, HashSet}, rc::Rc};
 
 use crate::object::Object;
 
 #[derive(Clone)]
 pub struct Environment {
     pub parent: Option<Rc<RefCell<Environment>>>,
-    env: HashMap<String, Object>
+    env: HashMap<String, Object>,
+    constants: HashSet<String>
 }
 
 impl Environment {
@@ -13,34 +14,55 @@ impl Environment {
     pub fn new(parent: Option<Rc<RefCell<Environment>>>) -> Self {
         Environment {
             parent,
-            env: HashMap::new()
+            env: HashMap::new(),
+            constants: HashSet::new()
+        }
+    }
+
+    pub fn root(environment: &Rc<RefCell<Environment>>) -> Rc<RefCell<Environment>> {
+        match environment.borrow().parent.as_ref() {
+            Some(parent) => Environment::root(parent),
+            None => environment.clone()
         }
     }
 
     pub fn define(&mut self, name: String, value: Object) {
+        self.constants.remove(&name);
+        self.env.insert(name, value);
+    }
+
+    pub fn define_constant(&mut self, name: String, value: Object) {
+        self.constants.insert(name.clone());
         self.env.insert(name, value);
     }
 
-    pub fn assign(&mut self, name: String, value: Object) {
+    pub fn assign(&mut self, name: String, value: Object) -> Result<(), String> {
+        if self.constants.contains(&name) {
+            return Err(format!("Can't assign to constant '{}'.", name));
+        }
+
         if self.env.contains_key(&name) {
             self.env.insert(name, value);
+            return Ok(());
         } else {
             if let Some(p) = self.parent.as_mut() {
-                p.borrow_mut().assign(name, value);
+                return p.borrow_mut().assign(name, value);
             }
         }
+
+        Err(format!("Undefined variable '{}'.", name))
     }
 
-    pub fn get(&self, name: &String) -> Object {
+    pub fn get(&self, name: &String) -> Result<Object, String> {
         if self.env.contains_key(name) {
-            return self.env.get(name).unwrap().clone();
+            return Ok(self.env.get(name).unwrap().clone());
         } else {
             if let Some(p) = self.parent.as_ref() {
                 return p.borrow().get(name);
             }
         }
 
-        panic!("Variable {} not defined.", name);
+        Err(format!("Undefined variable '{}'.", name))
     }
 
     pub fn get_return_value(&mut self) -> Object {
@@ -1,3 +1,6 @@
+use std::ops::Deref;
+
+use crate::object::Object;
 
 pub fn error(line: usize, message: String) {
     report(line, String::new(), message);
@@ -5,4 +8,43 @@ pub fn error(line: usize, message: String) {
 
 pub fn report(line: usize, location: String, message: String) {
     eprintln!("[line {line}] Error {location}: {message}");
-}
\ No newline at end of file
+}
+
+pub fn warn(line: usize, location: String, message: String) {
+    eprintln!("[line {line}] Warning {location}: {message}");
+}
+
+pub fn report_exception(exception: &Exception) {
+    eprintln!("[line {}] Uncaught {}", exception.line, exception.message);
+    for frame in &exception.stack_trace {
+        eprintln!("    at {frame}");
+    }
+}
+
+/// A thrown value on its way up the call stack. It is boxed so that a
+/// `Result<Object, Exception>`, which every step of the interpreter's recursion
+/// returns, is no bigger than an `Object`.
+#[derive(Clone)]
+pub struct Exception(Box<Thrown>);
+
+#[derive(Clone)]
+pub struct Thrown {
+    pub value: Object,
+    pub message: String,
+    pub line: usize,
+    pub stack_trace: Vec<String>,
+}
+
+impl Exception {
+    pub fn new(value: Object, message: String, line: usize, stack_trace: Vec<String>) -> Self {
+        Exception(Box::new(Thrown { value, message, line, stack_trace }))
+    }
+}
+
+impl Deref for Exception {
+    type Target = Thrown;
+
+    fn deref(&self) -> &Thrown {
+        &self.0
+    }
+}
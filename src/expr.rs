@@ -22,11 +22,21 @@ pub enum ExprType {
     Assign(Box<Token>, Box<Expr>),
     Binary(Box<Expr>, Box<Token>, Box<Expr>),
     Call(Box<Expr>, Box<Token>, Vec<Expr>),
+    CompoundAssign(Box<Token>, Box<Token>, Box<Expr>, bool),
+    CompoundSet(Box<Expr>, Box<Token>, Box<Token>, Box<Expr>, bool),
+    CompoundSetIndex(Box<Expr>, Box<Token>, Box<Expr>, Box<Token>, Box<Expr>, bool),
+    Conditional(Box<Expr>, Box<Expr>, Box<Expr>),
     Get(Box<Expr>, Box<Token>),
     Grouping(Box<Expr>),
+    Index(Box<Expr>, Box<Token>, Box<Expr>),
+    List(Vec<Expr>),
     Literal(Object),
     Logical(Box<Expr>, Box<Token>, Box<Expr>),
+    OptionalChain(Box<Expr>),
+    OptionalGet(Box<Expr>, Box<Token>),
+    Range(Box<Expr>, Box<Token>, Box<Expr>, Option<Box<Expr>>),
     Set(Box<Expr>, Box<Token>, Box<Expr>),
+    SetIndex(Box<Expr>, Box<Token>, Box<Expr>, Box<Expr>),
     Super(Box<Token>, Box<Token>),
     This(Box<Token>),
     Unary(Box<Token>, Box<Expr>),
@@ -0,0 +1,37 @@
+use std::{cell::RefCell, fmt::Display, rc::Rc};
+
+use crate::{function::Function, generator::Body};
+
+/// A Lox call stack that can be suspended with `Fiber.yield` anywhere inside
+/// it and resumed later with `call` or `transfer`.
+pub struct Fiber {
+    pub name: String,
+    pub state: Option<Body>,
+    pub done: bool,
+}
+
+impl Fiber {
+
+    /// Creates a fiber running `function`. A function with a parameter receives
+    /// the value the fiber is first called with.
+    pub fn new(function: Rc<RefCell<Function>>) -> Self {
+        let name = function.borrow().name.clone();
+        let body = Body::new(move |interpreter, value, line| {
+            let arguments = if function.borrow().max_arity() == Some(0) { Vec::new() } else { vec![value] };
+            interpreter.call_function(function, arguments, line)
+        });
+
+        Fiber {
+            name,
+            state: Some(body),
+            done: false
+        }
+    }
+
+}
+
+impl Display for Fiber {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} fiber", self.name)
+    }
+}
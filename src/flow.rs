@@ -0,0 +1,228 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::{error::warn, expr::{Expr, ExprType}, object::Object, stmt::{Case, Pattern, Stmt}, token::Token};
+
+/// Warns about statements that can never run, functions that return a value on
+/// some paths but fall off the end on others, and `while (true)` loops nothing
+/// can leave.
+pub fn check_flow(statements: &[Stmt]) {
+    Flow::new(statements).visit_statements(statements);
+}
+
+struct Flow {
+    // Members of the top-level enums, by enum name.
+    enums: HashMap<String, Vec<String>>,
+}
+
+impl Flow {
+
+    fn new(statements: &[Stmt]) -> Self {
+        let enums = statements.iter().filter_map(|statement| match statement {
+            Stmt::Enum(name, members) => Some((name, members)),
+            Stmt::Export(declaration) => match declaration.as_ref() {
+                Stmt::Enum(name, members) => Some((name, members)),
+                _ => None
+            },
+            _ => None
+        });
+        Flow {
+            enums: enums.map(|(name, members)| (name.lexeme.clone(), members.iter().map(|member| member.lexeme.clone()).collect())).collect()
+        }
+    }
+
+    fn warning(&self, token: &Token, message: String) {
+        warn(token.line, format!("at {}", token.lexeme), message);
+    }
+
+    /// Checks a list of statements and warns once about the statements
+    /// following one that never completes.
+    fn visit_statements(&self, statements: &[Stmt]) {
+        let mut unreachable = false;
+        for (i, statement) in statements.iter().enumerate() {
+            self.visit_stmt(statement);
+            if unreachable || i + 1 == statements.len() {
+                continue;
+            }
+            if let Some(exit) = self.exits(statement) {
+                self.warning(exit, format!("Code after '{}' is unreachable.", exit.lexeme));
+                unreachable = true;
+            }
+        }
+    }
+
+    fn visit_function(&self, name: &Token, body: &[Stmt], generator: bool) {
+        self.visit_statements(body);
+        if !generator && name.lexeme != "init" && body.iter().any(returns_value) && !body.iter().any(|statement| self.exits(statement).is_some()) {
+            self.warning(name, format!("Function '{}' returns a value on some paths but not on all.", name.lexeme));
+        }
+    }
+
+    /// The `return`, `throw` or `while` that keeps a statement from ever
+    /// completing normally, if it can't complete. Lox has no `break`, so only
+    /// returning or throwing leaves a `while (true)` loop.
+    fn exits<'a>(&self, statement: &'a Stmt) -> Option<&'a Token> {
+        match statement {
+            Stmt::Return(keyword, _) | Stmt::Throw(keyword, _) => Some(keyword),
+            Stmt::While(keyword, condition, _) if infinite(condition) => Some(keyword),
+            Stmt::Block(statements) => statements.iter().find_map(|statement| self.exits(statement)),
+            Stmt::If(_, then_branch, else_branch) => {
+                let then_exit = self.exits(then_branch)?;
+                self.exits(else_branch.as_ref().as_ref()?)?;
+                Some(then_exit)
+            },
+            Stmt::Match(_, _, cases) => {
+                // Only a match with a case for every value covers all paths.
+                let exhaustive = self.covers_enum(cases) || cases.iter().any(|case| case.guard.is_none()
+                    && case.patterns.iter().any(|pattern| matches!(pattern, Pattern::Wildcard | Pattern::Binding(_))));
+                let mut exit = None;
+                for case in cases {
+                    exit = Some(self.exits(&case.body)?);
+                }
+                if exhaustive { exit } else { None }
+            },
+            Stmt::Try(body, catch, finally) => {
+                if let Some(exit) = finally.as_ref().as_ref().and_then(|finally| finally.iter().find_map(|statement| self.exits(statement))) {
+                    return Some(exit);
+                }
+                let body_exit = body.iter().find_map(|statement| self.exits(statement))?;
+                match catch.as_ref() {
+                    Some((_, catch)) => catch.iter().find_map(|statement| self.exits(statement)),
+                    None => Some(body_exit)
+                }
+            },
+            _ => None
+        }
+    }
+
+    /// Whether the unguarded cases of a match name every member of an enum,
+    /// as in `case Light.Red` for each member of `Light`.
+    fn covers_enum(&self, cases: &[Case]) -> bool {
+        let mut covered: HashMap<&str, HashSet<&str>> = HashMap::new();
+        for pattern in cases.iter().filter(|case| case.guard.is_none()).flat_map(|case| &case.patterns) {
+            if let Pattern::Value(Expr { expr_type: ExprType::Get(enumeration, member), .. }) = pattern {
+                if let ExprType::Variable(enumeration) = &enumeration.expr_type {
+                    covered.entry(&enumeration.lexeme).or_default().insert(&member.lexeme);
+                }
+            }
+        }
+        covered.iter().any(|(enumeration, members)| self.enums.get(*enumeration)
+            .is_some_and(|all| all.iter().all(|member| members.contains(member.as_str()))))
+    }
+
+    fn visit_stmt(&self, stmt: &Stmt) {
+        match stmt {
+            Stmt::Block(statements) => self.visit_statements(statements),
+            Stmt::Class(_, _, _, methods, getters, setters, _, _) | Stmt::Trait(_, methods, getters, setters) => {
+                for member in methods.iter().chain(getters).chain(setters) {
+                    self.visit_stmt(member);
+                }
+            },
+            Stmt::Export(declaration) => self.visit_stmt(declaration),
+            Stmt::ForIn(_, _, body) => self.visit_stmt(body),
+            Stmt::Function(name, _, body, generator, _) => self.visit_function(name, body, *generator),
+            Stmt::If(_, then_branch, else_branch) => {
+                self.visit_stmt(then_branch);
+                if let Some(else_branch) = else_branch.as_ref() {
+                    self.visit_stmt(else_branch);
+                }
+            },
+            Stmt::Match(_, _, cases) => {
+                for case in cases {
+                    self.visit_stmt(&case.body);
+                }
+            },
+            Stmt::Try(body, catch, finally) => {
+                self.visit_statements(body);
+                if let Some((_, catch)) = catch.as_ref() {
+                    self.visit_statements(catch);
+                }
+                if let Some(finally) = finally.as_ref() {
+                    self.visit_statements(finally);
+                }
+            },
+            Stmt::While(keyword, condition, body) => {
+                if infinite(condition) && !can_leave(body) {
+                    self.warning(keyword, String::from("Loop never exits."));
+                }
+                self.visit_stmt(body);
+            },
+            _ => {}
+        }
+    }
+
+}
+
+/// Whether a loop condition is the literal `true`.
+fn infinite(condition: &Expr) -> bool {
+    match &condition.expr_type {
+        ExprType::Literal(Object::Boolean(true)) => true,
+        ExprType::Grouping(condition) => infinite(condition),
+        _ => false
+    }
+}
+
+/// Whether a statement contains a `return` with a value outside of nested functions.
+fn returns_value(statement: &Stmt) -> bool {
+    match statement {
+        Stmt::Return(_, value) => value.is_some(),
+        Stmt::Block(statements) => statements.iter().any(returns_value),
+        Stmt::ForIn(_, _, body) | Stmt::While(_, _, body) => returns_value(body),
+        Stmt::If(_, then_branch, else_branch) => returns_value(then_branch) || else_branch.as_ref().as_ref().is_some_and(returns_value),
+        Stmt::Match(_, _, cases) => cases.iter().any(|case| returns_value(&case.body)),
+        Stmt::Try(body, catch, finally) => body.iter()
+            .chain(catch.as_ref().iter().flat_map(|(_, catch)| catch))
+            .chain(finally.as_ref().iter().flatten())
+            .any(returns_value),
+        _ => false
+    }
+}
+
+/// Whether a loop body may leave the loop, through a statement or by an
+/// expression that may throw or suspend the fiber running the loop.
+fn can_leave(statement: &Stmt) -> bool {
+    match statement {
+        Stmt::Return(_, _) | Stmt::Throw(_, _) | Stmt::Yield(_, _) => true,
+        Stmt::Assign(targets, _, values) => targets.iter().chain(values).any(may_leave),
+        Stmt::Block(statements) => statements.iter().any(can_leave),
+        Stmt::Const(_, expr, _) | Stmt::Var(_, expr, _) | Stmt::VarDestructure(_, expr)
+            | Stmt::Expression(expr) | Stmt::Print(_, expr) => may_leave(expr),
+        Stmt::ForIn(_, iterable, body) => may_leave(iterable) || can_leave(body),
+        Stmt::If(condition, then_branch, else_branch) => may_leave(condition) || can_leave(then_branch)
+            || else_branch.as_ref().as_ref().is_some_and(can_leave),
+        Stmt::Match(_, subject, cases) => may_leave(subject) || cases.iter().any(|case| can_leave(&case.body)
+            || case.guard.as_ref().is_some_and(may_leave)),
+        Stmt::Try(body, catch, finally) => body.iter()
+            .chain(catch.as_ref().iter().flat_map(|(_, catch)| catch))
+            .chain(finally.as_ref().iter().flatten())
+            .any(can_leave),
+        Stmt::While(_, condition, body) => may_leave(condition) || can_leave(body),
+        _ => false
+    }
+}
+
+/// Whether evaluating an expression may leave the loop it is in. Calls may
+/// throw or suspend the fiber, and property access, indexing and operators on
+/// anything but literals may run getters, setters or operator methods, or fail.
+fn may_leave(expr: &Expr) -> bool {
+    match &expr.expr_type {
+        ExprType::Call(_, _, _) | ExprType::Get(_, _) | ExprType::OptionalGet(_, _) | ExprType::Index(_, _, _)
+            | ExprType::Set(_, _, _) | ExprType::SetIndex(_, _, _, _) | ExprType::CompoundSet(_, _, _, _, _)
+            | ExprType::CompoundSetIndex(_, _, _, _, _, _) | ExprType::CompoundAssign(_, _, _, _) | ExprType::Super(_, _) => true,
+        ExprType::Binary(left, _, right) => !literal(left) || !literal(right),
+        ExprType::Unary(_, expr) => !literal(expr),
+        ExprType::Range(start, _, end, step) => !literal(start) || !literal(end) || step.as_ref().is_some_and(|step| !literal(step)),
+        ExprType::Assign(_, value) | ExprType::Grouping(value) | ExprType::OptionalChain(value) => may_leave(value),
+        ExprType::Logical(left, _, right) => may_leave(left) || may_leave(right),
+        ExprType::Conditional(condition, then_branch, else_branch) => may_leave(condition) || may_leave(then_branch) || may_leave(else_branch),
+        ExprType::List(elements) => elements.iter().any(may_leave),
+        ExprType::Literal(_) | ExprType::This(_) | ExprType::Variable(_) => false,
+    }
+}
+
+fn literal(expr: &Expr) -> bool {
+    match &expr.expr_type {
+        ExprType::Literal(_) => true,
+        ExprType::Grouping(expr) => literal(expr),
+        _ => false
+    }
+}
@@ -1,25 +1,37 @@
 use std::{cell::RefCell, rc::Rc, fmt::Display};
 
-use crate::{environment::Environment, stmt::Stmt, object::Object};
+use crate::{environment::Environment, stmt::{Parameter, Stmt}, object::Object, token::source_name};
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum FunctionKind {
+    Function,
+    Initializer,
+    Method,
+    Getter,
+    Setter,
+}
 
 #[derive(Clone)]
 pub struct Function {
     pub name: String,
-    pub params: Vec<String>,
+    pub params: Rc<Vec<Parameter>>,
     pub body: Rc<Vec<Stmt>>,
     pub closure: Rc<RefCell<Environment>>,
-    pub is_initializer: bool,
+    pub kind: FunctionKind,
+    pub generator: bool,
 }
 
 impl Function {
 
-    pub fn new(name: String, params: Vec<String>, body: Rc<Vec<Stmt>>, closure: Rc<RefCell<Environment>>, is_initializer: bool) -> Self {
+    pub fn new(name: String, params: Rc<Vec<Parameter>>, body: Rc<Vec<Stmt>>, closure: Rc<RefCell<Environment>>, kind: FunctionKind,
+        generator: bool) -> Self {
         Function {
             name,
             params, 
             body,
             closure,
-            is_initializer
+            kind,
+            generator
         }
     }
 
@@ -31,17 +43,74 @@ impl Function {
             self.params.clone(), 
             self.body.clone(), 
             Rc::new(RefCell::new(environment)),
-                self.is_initializer)
+                self.kind,
+                self.generator)
+    }
+
+    pub fn is_initializer(&self) -> bool {
+        self.kind == FunctionKind::Initializer
+    }
+
+    pub fn min_arity(&self) -> usize {
+        min_arity(&self.params)
+    }
+
+    pub fn max_arity(&self) -> Option<usize> {
+        max_arity(&self.params)
+    }
+
+    /// Whether two functions are the same declaration with the same `this`.
+    /// Each access to a method binds it anew, so `a.foo == a.foo` can't rely
+    /// on identity.
+    pub fn same_binding(&self, other: &Function) -> bool {
+        if !Rc::ptr_eq(&self.body, &other.body) {
+            return false;
+        }
+        if Rc::ptr_eq(&self.closure, &other.closure) {
+            return true;
+        }
+        if self.kind == FunctionKind::Function {
+            return false;
+        }
+        let (l, r) = (self.closure.borrow(), other.closure.borrow());
+        let this = String::from("this");
+        match (&l.parent, &r.parent) {
+            (Some(l_parent), Some(r_parent)) => Rc::ptr_eq(l_parent, r_parent) && l.get(&this).ok() == r.get(&this).ok(),
+            _ => false
+        }
     }
 
-    pub fn arity(&self) -> usize {
-        self.params.len()
+}
+
+/// Number of arguments without a default value.
+pub fn min_arity(params: &[Parameter]) -> usize {
+    params.iter().filter(|param| param.default.is_none() && !param.rest).count()
+}
+
+/// Largest number of arguments accepted, `None` if there is a rest parameter.
+pub fn max_arity(params: &[Parameter]) -> Option<usize> {
+    match params.last() {
+        Some(param) if param.rest => None,
+        _ => Some(params.len())
     }
+}
 
+/// The error message for calling something taking `min` to `max` arguments
+/// with `count` arguments, if that is the wrong number.
+pub fn arity_error(min: usize, max: Option<usize>, count: usize) -> Option<String> {
+    if count >= min && max.is_none_or(|max| count <= max) {
+        return None;
+    }
+    let expected = match max {
+        Some(max) if max == min => format!("{}", min),
+        Some(max) => format!("{} to {}", min, max),
+        None => format!("at least {}", min)
+    };
+    Some(format!("Expected {} arguments but got {}.", expected, count))
 }
 
 impl Display for Function {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{} function", self.name)
+        write!(f, "{} function", source_name(&self.name))
     }
 }
\ No newline at end of file
@@ -0,0 +1,87 @@
+use std::{fmt::Display, io};
+
+use corosensei::{stack::DefaultStack, Coroutine, CoroutineResult, Yielder};
+
+use crate::{error::Exception, interpreter::Interpreter, object::Object};
+
+/// Stack size of a suspended Lox call stack. It is only reserved, so pages
+/// are committed as deep recursion inside the coroutine touches them.
+const STACK_SIZE: usize = 16 * 1024 * 1024;
+
+/// Number of calls a coroutine may nest on its stack. Deeper recursion raises
+/// an error instead of overflowing the stack, with room to spare for the
+/// largest interpreter frames of a debug build.
+pub const MAX_DEPTH: usize = 400;
+
+/// Value passed into a coroutine when it is resumed: the frames of the
+/// resumer's call stack, the value handed to the suspended code and the line
+/// it is resumed from.
+pub type Resume = (Vec<(String, usize)>, Object, usize);
+
+pub type Suspender = Yielder<Resume, Object>;
+
+type Start = Box<dyn FnOnce(&mut Interpreter, Object, usize) -> Result<Object, Exception>>;
+
+/// The Lox call stack of a generator or fiber. It runs on an interpreter of its
+/// own, which lives on the coroutine's stack, so nothing borrows it while the
+/// coroutine is suspended. The stack is only allocated when it first runs.
+pub struct Body {
+    start: Option<Start>,
+    coroutine: Option<Coroutine<Resume, Object, Result<Object, Exception>, DefaultStack>>,
+}
+
+impl Body {
+
+    pub fn new<F>(start: F) -> Self
+    where F: FnOnce(&mut Interpreter, Object, usize) -> Result<Object, Exception> + 'static {
+        Body {
+            start: Some(Box::new(start)),
+            coroutine: None
+        }
+    }
+
+    /// Runs the coroutine until it yields or returns. On the first resume its
+    /// stack is allocated and `interpreter` creates the interpreter it runs on.
+    pub fn resume(&mut self, resume: Resume, interpreter: impl FnOnce() -> Interpreter) -> io::Result<CoroutineResult<Object, Result<Object, Exception>>> {
+        if self.coroutine.is_none() {
+            let stack = DefaultStack::new(STACK_SIZE)?;
+            let start = self.start.take().expect("A coroutine that hasn't run has code to start.");
+            let mut interpreter = interpreter();
+            self.coroutine = Some(Coroutine::with_stack(stack, move |suspender: &Suspender, (frames, value, line): Resume| {
+                interpreter.enter(suspender, frames, line);
+                start(&mut interpreter, value, line)
+            }));
+        }
+        Ok(self.coroutine.as_mut().expect("The coroutine was started above.").resume(resume))
+    }
+
+}
+
+/// A suspended generator function call. Values are produced lazily, one per
+/// `yield`, whenever the generator is asked for its next element. While the
+/// generator runs, its body is taken out of it.
+pub struct Generator {
+    pub name: String,
+    pub state: Option<Body>,
+    pub done: bool,
+    pub buffered: Option<Object>,
+}
+
+impl Generator {
+
+    pub fn new(name: String, body: Body) -> Self {
+        Generator {
+            name,
+            state: Some(body),
+            done: false,
+            buffered: None
+        }
+    }
+
+}
+
+impl Display for Generator {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} generator", self.name)
+    }
+}
@@ -1,6 +1,6 @@
 use std::{rc::Rc, cell::RefCell, collections::HashMap, fmt::Display};
 
-use crate::{class::Class, object::Object};
+use crate::{class::Class, function::Function, object::Object, token::source_name};
 
 pub struct Instance {
     pub class: Rc<RefCell<Class>>,
@@ -16,23 +16,48 @@ impl Instance {
         }
     }
 
-    pub fn get(&self, name: &str, instance: &Object) -> Object {
+    pub fn get(&self, name: &str, instance: &Object) -> Result<Object, String> {
+        if let Some(Object::Function(getter)) = self.class.borrow().find_getter(name) {
+            let getter = getter.borrow().bind(instance.clone());
+            return Ok(Object::Function(Rc::new(RefCell::new(getter))));
+        }
+
         if let Some(value) = self.fields.get(name) {
-            return value.clone();
+            return Ok(value.clone());
         } 
         
         if let Some(method) = self.class.borrow().find_method(name) {
             if let Object::Function(function) = method {
                 let function = function.borrow_mut().bind(instance.clone());
-                return Object::Function(Rc::new(RefCell::new(function)));
+                return Ok(Object::Function(Rc::new(RefCell::new(function))));
             }
         }
 
-        panic!("undefined property '{}'", name);
+        Err(format!("Undefined property '{}'.", source_name(name)))
+    }
+
+    pub fn field(&self, name: &str) -> Option<Object> {
+        self.fields.get(name).cloned()
+    }
+
+    pub fn field_names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.fields.keys().cloned().collect();
+        names.sort();
+        names
     }
 
-    pub fn set(&mut self, name: String, value: Object) {
+    /// Sets a field without going through setters.
+    pub fn define(&mut self, name: String, value: Object) {
+        self.fields.insert(name, value);
+    }
+
+    pub fn set(&mut self, name: String, value: Object, instance: &Object) -> Option<Function> {
+        if let Some(Object::Function(setter)) = self.class.borrow().find_setter(&name) {
+            return Some(setter.borrow().bind(instance.clone()));
+        }
+
         self.fields.insert(name, value);
+        None
     }
 
 }
@@ -1,18 +1,51 @@
-use std::{cell::RefCell, collections::HashMap, iter::zip, rc::Rc};
+use corosensei::CoroutineResult;
 
-use crate::{class::Class, environment::Environment, expr::{Expr, ExprType}, function::Function, instance::Instance, native::NativeFunction, object::Object, stmt::Stmt, token::TokenType};
+use std::{cell::RefCell, collections::{hash_map::DefaultHasher, HashMap}, fs::read_to_string, hash::{Hash, Hasher}, path::PathBuf, rc::Rc};
 
+use crate::{class::{Class, Field, Trait}, enumeration::Enum, environment::Environment, error::Exception, expr::{Expr, ExprType}, fiber::Fiber, function::{arity_error, Function, FunctionKind}, generator::{Body, Generator, Suspender, MAX_DEPTH}, instance::Instance, map::Map, module::{exports, resolve_path, Exports}, native::NativeFunction, object::Object, optimizer::optimize, parser::parse, range::Range, resolver::resolve, scanner::scan_tokens, stmt::{Case, Destructure, Parameter, Pattern, Stmt}, token::{source_name, Token, TokenType}};
 
-pub trait Visitor<T> {
-    fn visit_expr(&mut self, expr: &Expr) -> T;
-    fn visit_stmt(&mut self, stmt: &Stmt);
+pub const PRELUDE: &str = "
+class Error {
+    init(message) {
+        this.message = message;
+    }
+}
+";
+
+pub trait Visitor<E, S> {
+    fn visit_expr(&mut self, expr: &Expr) -> E;
+    fn visit_stmt(&mut self, stmt: &Stmt) -> S;
 }
 
+/// Runs one Lox call stack. Generators and fibers each run on an interpreter
+/// of their own, which shares everything but the call stack with the one
+/// running the script.
 pub struct Interpreter {
     globals: Rc<RefCell<Environment>>,
+    shared: Rc<RefCell<Shared>>,
     environment: Rc<RefCell<Environment>>,
-    locals: HashMap<usize, usize>,
     returning: bool,
+    frames: Vec<(String, usize)>,
+    // How many frames at the bottom of `frames` belong to whoever resumed the
+    // coroutine this interpreter runs, so stack traces show both.
+    caller_frames: usize,
+    short_circuit: bool,
+    suspender: Option<*const Suspender>,
+    // Set when this interpreter runs the call stack of a fiber.
+    fiber: bool,
+}
+
+/// State shared by the interpreter running a script and the interpreters
+/// running its generators and fibers.
+struct Shared {
+    locals: HashMap<usize, usize>,
+    error_class: Option<Rc<RefCell<Class>>>,
+    builtins: Environment,
+    modules: HashMap<PathBuf, Rc<Exports>>,
+    loading: Vec<PathBuf>,
+    search_path: Vec<PathBuf>,
+    // The fiber a suspending fiber transfers control to.
+    transfer: Option<Rc<RefCell<Fiber>>>,
 }
 
 impl Interpreter {
@@ -22,328 +55,1769 @@ impl Interpreter {
             Rc::new(RefCell::new(Environment::new(None)));
         let environment = globals.clone();
 
-        globals.borrow_mut().define(NativeFunction::Clock.to_string(), Object::NativeFunction(NativeFunction::Clock));
-        globals.borrow_mut().define(NativeFunction::Input.to_string(), Object::NativeFunction(NativeFunction::Input));
-        globals.borrow_mut().define(NativeFunction::ReadFile.to_string(), Object::NativeFunction(NativeFunction::ReadFile));
+        for native in NativeFunction::ALL {
+            globals.borrow_mut().define(native.to_string(), Object::NativeFunction(native));
+        }
 
-        Interpreter {
+        let shared = Shared {
+            locals,
+            error_class: None,
+            builtins: Environment::new(None),
+            modules: HashMap::new(),
+            loading: Vec::new(),
+            search_path: Vec::new(),
+            transfer: None,
+        };
+        let mut interpreter = Interpreter {
             globals,
+            shared: Rc::new(RefCell::new(shared)),
             environment,
-            locals,
             returning: false,
+            frames: Vec::new(),
+            caller_frames: 0,
+            short_circuit: false,
+            suspender: None,
+            fiber: false,
+        };
+        interpreter.load_prelude();
+        interpreter
+    }
+
+    fn load_prelude(&mut self) {
+        let statements = parse(scan_tokens(PRELUDE.to_string()));
+        self.extend_locals(resolve(&statements));
+        if self.interpret(&statements).is_err() {
+            panic!("Could not load prelude.");
+        }
+
+        if let Ok(Object::Class(class)) = self.globals.borrow().get(&String::from("Error")) {
+            self.shared.borrow_mut().error_class = Some(class);
         }
+
+        self.shared.borrow_mut().builtins = self.globals.borrow().clone();
+    }
+
+    /// Sets the script being run, which relative imports are resolved against.
+    pub fn set_script_path(&mut self, path: PathBuf) {
+        self.shared.borrow_mut().loading = vec![path.canonicalize().unwrap_or(path)];
+    }
+
+    pub fn set_search_path(&mut self, search_path: Vec<PathBuf>) {
+        self.shared.borrow_mut().search_path = search_path;
     }
     
-    pub fn interpret(&mut self, statements: &Vec<Stmt>) {
+    pub fn interpret(&mut self, statements: &Vec<Stmt>) -> Result<(), Exception> {
         for statement in statements {
-            self.visit_stmt(statement);
+            self.visit_stmt(statement)?;
         }
+        Ok(())
     }
 
     pub fn extend_locals(&mut self, locals: HashMap<usize, usize>) {
-        self.locals.extend(locals);
+        self.shared.borrow_mut().locals.extend(locals);
     }
 
-    fn execute_block(&mut self, statements: &Vec<Stmt>, environment: Rc<RefCell<Environment>>) {
+    fn execute_block(&mut self, statements: &[Stmt], environment: Rc<RefCell<Environment>>) -> Result<(), Exception> {
         let tmp = self.environment.clone();
         self.environment = environment;
+        let mut result = Ok(());
         for statement in statements {
-            self.visit_stmt(statement);
+            result = self.visit_stmt(statement);
+            if result.is_err() {
+                break;
+            }
         }
         self.environment = tmp;
+        result
     }
 
-    fn call_function(&mut self, function: Rc<RefCell<Function>>, arguments: Vec<Object>) -> Object {
+    pub fn call_function(&mut self, function: Rc<RefCell<Function>>, arguments: Vec<Object>, line: usize) -> Result<Object, Exception> {
 
         if function.borrow().name == "clock" {
-            return Object::Number(16.0);
+            return Ok(Object::Number(16.0));
         }
        
-        let arity = function.borrow().arity();
-        if arguments.len() != arity {
-            panic!("{}, Expected {} arguments but got {}.", 
-                function.borrow().name, arity, arguments.len());
+        let (min, max) = (function.borrow().min_arity(), function.borrow().max_arity());
+        if let Some(message) = arity_error(min, max, arguments.len()) {
+            return Err(self.error(line, message));
         }
 
-        let environment = Rc::new(RefCell::new(Environment::new(Some(function.borrow().closure.clone()))));
-        for (param, arg) in zip(&function.borrow().params, arguments) {
-            environment.borrow_mut().define(param.clone(), arg);
+        if function.borrow().generator {
+            return Ok(generator(function, arguments));
         }
 
-        self.execute_block(&function.borrow().body, environment);
+        self.execute_function(function, arguments, line)
+    }
+
+    fn execute_function(&mut self, function: Rc<RefCell<Function>>, arguments: Vec<Object>, line: usize) -> Result<Object, Exception> {
+        if self.suspender.is_some() && self.frames.len() - self.caller_frames >= MAX_DEPTH {
+            return Err(self.error(line, String::from("Stack overflow.")));
+        }
+
+        let environment = Rc::new(RefCell::new(Environment::new(Some(function.borrow().closure.clone()))));
+        let params = function.borrow().params.clone();
+
+        self.push_frame(&function, line);
+        let mut result = self.bind_parameters(&params, arguments, &environment);
+        if result.is_ok() {
+            result = self.execute_block(&function.borrow().body, environment);
+        }
+        self.frames.pop();
         self.returning = false;
+        result?;
         
         // handle return nil
-        if function.borrow().is_initializer {
-            return function.borrow().closure.borrow().get_at(0, &String::from("this"));
+        if function.borrow().is_initializer() {
+            return Ok(function.borrow().closure.borrow().get_at(0, &String::from("this")));
         } else {
-            return self.globals.borrow_mut().get_return_value();
+            return Ok(self.globals.borrow_mut().get_return_value());
+        }
+    }
+
+    #[inline(never)]
+    fn push_frame(&mut self, function: &Rc<RefCell<Function>>, line: usize) {
+        self.frames.push((format!("{}()", source_name(&function.borrow().name)), line));
+    }
+
+    /// Defines the parameters of a call. Missing arguments get their default value,
+    /// evaluated in the function's environment, and a rest parameter gets a list.
+    #[inline(never)]
+    fn bind_parameters(&mut self, params: &[Parameter], arguments: Vec<Object>, environment: &Rc<RefCell<Environment>>) -> Result<(), Exception> {
+        let enclosing = std::mem::replace(&mut self.environment, environment.clone());
+        let mut arguments = arguments.into_iter();
+        let mut result = Ok(());
+
+        for param in params {
+            let value = if param.rest {
+                Object::List(Rc::new(RefCell::new(arguments.by_ref().collect())))
+            } else if let Some(argument) = arguments.next() {
+                argument
+            } else if let Some(default) = &param.default {
+                match self.visit_expr(default) {
+                    Ok(value) => value,
+                    Err(exception) => {
+                        result = Err(exception);
+                        break;
+                    }
+                }
+            } else {
+                Object::Nil
+            };
+            environment.borrow_mut().define(param.name.lexeme.clone(), value);
         }
+
+        self.environment = enclosing;
+        result
     }
 
-    fn lookup_variable(&mut self, name: String, id: usize) -> Object {
-        match self.locals.get(&id) {
-            Some(distance) => self.environment.borrow().get_at(*distance, &name),
-            None => self.globals.borrow().get(&name)
+    /// An interpreter for the call stack of a generator or fiber.
+    fn spawn(&self, fiber: bool) -> Interpreter {
+        Interpreter {
+            globals: self.globals.clone(),
+            shared: self.shared.clone(),
+            environment: self.globals.clone(),
+            returning: false,
+            frames: Vec::new(),
+            caller_frames: 0,
+            short_circuit: false,
+            suspender: None,
+            fiber
         }
     }
- 
-}
 
-impl Visitor<Object> for Interpreter {
+    /// Starts running a coroutine on this interpreter.
+    pub fn enter(&mut self, suspender: &Suspender, frames: Vec<(String, usize)>, line: usize) {
+        self.suspender = Some(suspender);
+        self.resumed(frames, line);
+    }
 
-    fn visit_stmt(&mut self, stmt: &Stmt) {
-        if self.returning {
-            return;
+    /// Stacks the coroutine's own frames on top of those of its new resumer.
+    /// The outermost one is now called from the line it is resumed from.
+    fn resumed(&mut self, frames: Vec<(String, usize)>, line: usize) {
+        let mut own = self.frames.split_off(self.caller_frames);
+        if let Some(frame) = own.first_mut() {
+            frame.1 = line;
         }
+        self.caller_frames = frames.len();
+        self.frames = frames;
+        self.frames.append(&mut own);
+    }
 
-        match stmt {
-            Stmt::Expression(expr) => {self.visit_expr(expr);()},
-            Stmt::Print(expr) => println!("{}", self.visit_expr(expr)),
-            Stmt::Var(name, initializer) => {
-                let value = self.visit_expr(initializer);
-                self.environment.borrow_mut().define(name.lexeme.clone(), value);
+    /// Runs the call stack of a generator or fiber until it yields or returns.
+    fn resume(&mut self, body: &mut Body, fiber: bool, value: Object, line: usize) -> Result<CoroutineResult<Object, Result<Object, Exception>>, Exception> {
+        body.resume((self.frames.clone(), value, line), || self.spawn(fiber))
+            .map_err(|error| self.error(line, format!("Could not allocate a stack: {}.", error)))
+    }
+
+    /// Produces the next value of a generator, or `None` once it has finished.
+    fn next_value(&mut self, generator: &Rc<RefCell<Generator>>, line: usize) -> Result<Option<Object>, Exception> {
+        if let Some(value) = generator.borrow_mut().buffered.take() {
+            return Ok(Some(value));
+        }
+        if generator.borrow().done {
+            return Ok(None);
+        }
+
+        let state = generator.borrow_mut().state.take();
+        let mut body = match state {
+            Some(body) => body,
+            None => return Err(self.error(line, format!("{} is already running.", generator.borrow())))
+        };
+
+        let result = self.resume(&mut body, false, Object::Nil, line);
+        match result {
+            Ok(CoroutineResult::Yield(value)) => {
+                generator.borrow_mut().state = Some(body);
+                Ok(Some(value))
             },
-            Stmt::Block(statements) => {
-                let e = Rc::new(RefCell::new(Environment::new(Some(self.environment.clone()))));
-                self.execute_block(statements, e);
+            Ok(CoroutineResult::Return(result)) => {
+                generator.borrow_mut().done = true;
+                result.map(|_| None)
             },
-            Stmt::If(condition, then_branch, else_branch) => {
-                if self.visit_expr(condition).is_truthy() {
-                    self.visit_stmt(then_branch);
-                } else {
-                    if let Some(statement) = else_branch.as_ref() {
-                        self.visit_stmt(statement)
+            Err(exception) => {
+                generator.borrow_mut().state = Some(body);
+                Err(exception)
+            }
+        }
+    }
+
+    /// Suspends the coroutine this interpreter runs and returns the value it
+    /// is resumed with.
+    fn suspend(&mut self, value: Object) -> Option<Object> {
+        let suspender = self.suspender?;
+        // SAFETY: only an interpreter running a coroutine has a suspender. It
+        // lives on the coroutine's stack, like the interpreter itself.
+        let (frames, value, line) = unsafe { (*suspender).suspend(value) };
+        self.resumed(frames, line);
+        Some(value)
+    }
+
+    /// Runs a fiber until it yields or finishes and returns the yielded or
+    /// returned value. Transfers requested by the running fiber are carried out
+    /// here, so control moves on to the target fiber until one of them yields.
+    fn run_fiber(&mut self, fiber: &Rc<RefCell<Fiber>>, value: Object, line: usize) -> Result<Object, Exception> {
+        let mut fiber = fiber.clone();
+        let mut value = value;
+        loop {
+            let mut body = self.fiber_state(&fiber, line)?;
+            let result = self.resume(&mut body, true, value, line);
+
+            match result {
+                Ok(CoroutineResult::Yield(yielded)) => {
+                    fiber.borrow_mut().state = Some(body);
+                    let transfer = self.shared.borrow_mut().transfer.take();
+                    match transfer {
+                        Some(target) => {
+                            fiber = target;
+                            value = yielded;
+                        },
+                        None => return Ok(yielded)
                     }
+                },
+                Ok(CoroutineResult::Return(result)) => {
+                    fiber.borrow_mut().done = true;
+                    return result;
+                },
+                Err(exception) => {
+                    fiber.borrow_mut().state = Some(body);
+                    return Err(exception);
                 }
-            },
-            Stmt::While(condition, body) => {
-                while self.visit_expr(condition).is_truthy() {
-                    self.visit_stmt(body);
-                }
-            }
-            Stmt::Function(name, parameters, body) => {
-                let params = parameters.iter().map(|token| token.lexeme.clone()).collect();
-                let function = Function::new(name.lexeme.clone(), params, body.clone(), self.environment.clone(), false);
-                self.environment.borrow_mut().define(name.lexeme.clone(), Object::Function(Rc::new(RefCell::new(function))));
             }
-            Stmt::Return(value) => {
-                
-                match value.as_ref() {
-                    Some(value) => {
-                        let return_value = self.visit_expr(value);
-                        self.returning = true;
-                        self.globals.borrow_mut().define("return_value".to_string(), return_value);
-                    },
-                    None => {
-                        self.returning = true;
-                        self.globals.borrow_mut().define("return_value".to_string(), Object::Nil);
-                    }
+        }
+    }
+
+    /// Takes the suspended body out of a fiber that is about to run.
+    fn fiber_state(&mut self, fiber: &Rc<RefCell<Fiber>>, line: usize) -> Result<Body, Exception> {
+        if fiber.borrow().done {
+            return Err(self.error(line, String::from("Cannot resume a finished fiber.")));
+        }
+        let state = fiber.borrow_mut().state.take();
+        match state {
+            Some(body) => Ok(body),
+            None => Err(self.error(line, String::from("Fiber is already running.")))
+        }
+    }
+
+    fn stack_trace(&self, line: usize) -> Vec<String> {
+        let mut trace = Vec::new();
+        let mut line = line;
+        for (name, call_line) in self.frames.iter().rev() {
+            trace.push(format!("{name} [line {line}]"));
+            line = *call_line;
+        }
+        trace.push(format!("script [line {line}]"));
+        trace
+    }
+
+    /// Creates a catchable runtime error as an instance of the `Error` class.
+    fn error(&mut self, line: usize, message: String) -> Exception {
+        let error_class = self.shared.borrow().error_class.clone();
+        match error_class {
+            Some(class) => {
+                let instance = Object::Instance(Rc::new(RefCell::new(Instance::new(class))));
+                if let Object::Instance(ref error) = instance {
+                    error.borrow_mut().set(String::from("message"), Object::String(message), &instance);
                 }
+                self.throw(instance, line)
             },
-            Stmt::Class(name, superclass, methods) => {
-                let superclass = if let Some(superclass) = superclass.as_ref() {
-                    if let Object::Class(class) = self.visit_expr(superclass) {
-                        Some(class)
-                    } else {
-                        panic!("Superclass must be a class.");
+            None => panic!("[line {line}] {message}")
+        }
+    }
+
+    /// Turns a thrown value into an exception. Error instances get the line and
+    /// stack trace of the first throw, so rethrowing keeps the original origin.
+    fn throw(&mut self, value: Object, line: usize) -> Exception {
+        let mut message = self.stringify(&value, line).unwrap_or_else(|_| value.to_string());
+        let mut line = line;
+        let mut stack_trace = self.stack_trace(line);
+
+        let error_class = self.shared.borrow().error_class.clone();
+        if let (Object::Instance(instance), Some(error_class)) = (&value, &error_class) {
+            if instance.borrow().class.borrow().is_subclass_of(error_class) {
+                let origin = (instance.borrow().field("line"), instance.borrow().field("stackTrace"));
+                match origin {
+                    (Some(Object::Number(origin_line)), Some(Object::String(origin_trace))) => {
+                        line = origin_line as usize;
+                        stack_trace = origin_trace.lines().map(String::from).collect();
+                    },
+                    _ => {
+                        instance.borrow_mut().set(String::from("line"), Object::Number(line as f64), &value);
+                        instance.borrow_mut().set(String::from("stackTrace"), Object::String(stack_trace.join("\n")), &value);
                     }
-                } else {
-                    None
+                }
+
+                let class_name = instance.borrow().class.borrow().name.clone();
+                message = match instance.borrow().field("message") {
+                    Some(error_message) => format!("{}: {}", class_name, error_message),
+                    None => class_name
                 };
+            }
+        }
+
+        Exception::new(value, message, line, stack_trace)
+    }
 
-                self.environment.borrow_mut().define(name.lexeme.clone(), Object::Nil);
+    fn accessors(&self, declarations: &Vec<Stmt>, kind: FunctionKind) -> HashMap<String, Object> {
+        let mut accessors = HashMap::new();
+        for declaration in declarations {
+            if let Stmt::Function(name, parameters, body, generator, _) = declaration {
+                let function = Function::new(name.lexeme.clone(), parameters.clone(), body.clone(), self.environment.clone(),
+                    kind, *generator);
+                accessors.insert(name.lexeme.clone(), Object::Function(Rc::new(RefCell::new(function))));
+            } else {
+                panic!();
+            }
+        }
+        accessors
+    }
 
-                if let Some(sc) = superclass.as_ref() {
-                    self.environment = Rc::new(RefCell::new(Environment::new(Some(self.environment.clone()))));
-                    self.environment.borrow_mut().define(String::from("super"), Object::Class(sc.clone()));
+    /// Copies the members of the used traits into the members of a class. Members
+    /// the class defines itself win, the same member in two traits is a conflict.
+    fn use_traits(&mut self, class: &Token, traits: &[Rc<Trait>], members: &mut HashMap<String, Object>,
+        trait_members: impl Fn(&Trait) -> &HashMap<String, Object>) -> Result<(), Exception> {
+        let mut origins: HashMap<String, String> = HashMap::new();
+        for used in traits {
+            for (name, member) in trait_members(used) {
+                if let Some(origin) = origins.get(name) {
+                    return Err(self.error(class.line, format!("Class '{}' gets '{}' from both trait '{}' and trait '{}', it must define '{}' itself.",
+                        class.lexeme, name, origin, used.name, name)));
+                }
+                if members.contains_key(name) {
+                    continue;
                 }
+                members.insert(name.clone(), member.clone());
+                origins.insert(name.clone(), used.name.clone());
+            }
+        }
+        Ok(())
+    }
 
-                let mut methods2 = HashMap::new();
-                for method in methods {
-                    if let Stmt::Function(name, parameters, body) = method {
-                        let function = Function::new(name.lexeme.clone(), parameters.iter().map(|p|p.lexeme.clone()).collect(), body.clone(), self.environment.clone(),
-                            name.lexeme == "init");
-                        methods2.insert(name.lexeme.clone(), Object::Function(Rc::new(RefCell::new(function))));
-                    } else {
-                        panic!();
-                    }
+    fn lookup_variable(&mut self, name: &Token, id: usize) -> Result<Object, Exception> {
+        let distance = self.shared.borrow().locals.get(&id).copied();
+        let value = match distance {
+            Some(distance) => Ok(self.environment.borrow().get_at(distance, &name.lexeme)),
+            None => Environment::root(&self.environment).borrow().get(&name.lexeme)
+        };
+        value.map_err(|message| self.error(name.line, message))
+    }
+
+    fn binary(&mut self, left: Object, op: &Token, right: Object) -> Result<Object, Exception> {
+        if let Some(method) = operator_method(&op.token_type) {
+            if let Object::Instance(instance) = &left {
+                // Without `__add`, adding a string concatenates as it does for any other value.
+                let concatenates = op.token_type == TokenType::Plus && matches!(right, Object::String(_))
+                    && instance.borrow().class.borrow().find_method(method).is_none();
+                if !concatenates {
+                    return self.call_operator(&left, method, vec![right], op);
                 }
+            }
+        }
+        // `1 < v` is answered by `v > 1` when only the right operand is an instance.
+        if let Some(method) = reflected_method(&op.token_type) {
+            if let Object::Instance(_) = right {
+                return self.call_operator(&right, method, vec![left], op);
+            }
+        }
 
-                
-                let class = Rc::new(RefCell::new(Class::new(name.lexeme.clone(), superclass.clone(), methods2)));
+        if op.token_type == TokenType::EqualEqual || op.token_type == TokenType::BangEqual {
+            let equal = self.equals(&left, &right, op.line)?;
+            return Ok(Object::Boolean(equal == (op.token_type == TokenType::EqualEqual)));
+        }
+
+        if op.token_type == TokenType::Plus {
+            if let (Object::String(_), _) | (_, Object::String(_)) = (&left, &right) {
+                let left = self.stringify(&left, op.line)?;
+                let right = self.stringify(&right, op.line)?;
+                return Ok(Object::String(format!("{}{}", left, right)));
+            }
+        }
 
-                if superclass.is_some() {
-                    let parent = self.environment.borrow().parent.clone().unwrap();
-                    self.environment = parent;
+        // Members of the same enum are ordered by their position in the declaration.
+        if let (Object::EnumMember(l), Object::EnumMember(r)) = (&left, &right) {
+            if matches!(op.token_type, TokenType::Greater | TokenType::GreaterEqual | TokenType::Less | TokenType::LessEqual) {
+                if !l.same_enum(r) {
+                    return Err(self.error(op.line, String::from("Can only compare members of the same enum.")));
                 }
+                return self.binary(Object::Number(l.ordinal as f64), op, Object::Number(r.ordinal as f64));
+            }
+        }
 
-                self.environment.borrow_mut().assign(name.lexeme.clone(), Object::Class(class));
+        let value = match op.token_type {
+            TokenType::Minus => match (left, right) {
+                (Object::Number(l), Object::Number(r)) => Object::Number(l-r),
+                (_, _) => return Err(self.error(op.line, String::from("Operands must be numbers.")))
+            },
+            TokenType::Plus => match (left, right) {
+                (Object::Number(l), Object::Number(r)) => Object::Number(l+r),
+                (_, _) => return Err(self.error(op.line, String::from("Operands must be two numbers or two strings.")))
+            },
+            TokenType::Slash => match (left, right) {
+                (Object::Number(l), Object::Number(r)) => Object::Number(l/r),
+                (_, _) => return Err(self.error(op.line, String::from("Operands must be numbers.")))
             },
+            TokenType::Star => match (left, right) {
+                (Object::Number(l), Object::Number(r)) => Object::Number(l*r),
+                (_, _) => return Err(self.error(op.line, String::from("Operands must be numbers.")))
+            },
+            TokenType::Percent => match (left, right) {
+                (Object::Number(l), Object::Number(r)) => Object::Number(l - r * (l/r).floor()),
+                (_, _) => return Err(self.error(op.line, String::from("Operands must be numbers.")))
+            },
+            TokenType::TildeSlash => match (left, right) {
+                (Object::Number(l), Object::Number(r)) => Object::Number((l/r).floor()),
+                (_, _) => return Err(self.error(op.line, String::from("Operands must be numbers.")))
+            },
+            TokenType::StarStar => match (left, right) {
+                (Object::Number(l), Object::Number(r)) => Object::Number(l.powf(r)),
+                (_, _) => return Err(self.error(op.line, String::from("Operands must be numbers.")))
+            },
+            TokenType::Ampersand => {
+                let (l, r) = self.integer_operands(&left, &right, op)?;
+                Object::Number((l & r) as f64)
+            },
+            TokenType::Pipe => {
+                let (l, r) = self.integer_operands(&left, &right, op)?;
+                Object::Number((l | r) as f64)
+            },
+            TokenType::Caret => {
+                let (l, r) = self.integer_operands(&left, &right, op)?;
+                Object::Number((l ^ r) as f64)
+            },
+            TokenType::LessLess | TokenType::GreaterGreater => {
+                let (l, r) = self.integer_operands(&left, &right, op)?;
+                if !(0..64).contains(&r) {
+                    return Err(self.error(op.line, String::from("Shift amount must be between 0 and 63.")));
+                }
+                if op.token_type == TokenType::LessLess {
+                    Object::Number((l << r) as f64)
+                } else {
+                    Object::Number((l >> r) as f64)
+                }
+            },
+            TokenType::Is => Object::Boolean(self.is_instance(&left, &right, op.line)?),
+            TokenType::Greater => match (left, right) {
+                (Object::Number(l), Object::Number(r)) => Object::Boolean(l>r),
+                (_, _) => return Err(self.error(op.line, String::from("Operands must be numbers.")))
+            },
+            TokenType::GreaterEqual => match (left, right) {
+                (Object::Number(l), Object::Number(r)) => Object::Boolean(l>=r),
+                (_, _) => return Err(self.error(op.line, String::from("Operands must be numbers.")))
+            },
+            TokenType::Less => match (left, right) {
+                (Object::Number(l), Object::Number(r)) => Object::Boolean(l<r),
+                (_, _) => return Err(self.error(op.line, String::from("Operands must be numbers.")))
+            },
+            TokenType::LessEqual => match (left, right) {
+                (Object::Number(l), Object::Number(r)) => Object::Boolean(l<=r),
+                (_, _) => return Err(self.error(op.line, String::from("Operands must be numbers.")))
+            },
+            _ => panic!()
         };
+        Ok(value)
     }
 
-    fn visit_expr(&mut self, expr: &Expr) -> Object {
-        match &expr.expr_type {
-            ExprType::Binary(left, op, right) => {
-                let left = self.visit_expr(&left);
-                let right = self.visit_expr(&right);
+    /// Sets the declared fields of a new instance, those of its superclasses first.
+    fn initialize_fields(&mut self, class: &Rc<RefCell<Class>>, instance: &Object) -> Result<(), Exception> {
+        if let Some(superclass) = class.borrow().superclass() {
+            self.initialize_fields(&superclass, instance)?;
+        }
 
-                match op.token_type {
-                    TokenType::Minus => match (left, right) {
-                        (Object::Number(l), Object::Number(r)) => Object::Number(l-r),
-                        (_, _) => panic!()
-                    },
-                    TokenType::Plus => match (left, right) {
-                        (Object::Number(l), Object::Number(r)) => Object::Number(l+r),
-                        (Object::String(l), Object::String(r)) => Object::String(format!("{}{}",l,r)),
-                        (Object::String(l), Object::Number(r)) => Object::String(format!("{}{}",l,r)),
-                        (Object::Number(l), Object::String(r)) => Object::String(format!("{}{}",l,r)),
-                        (Object::String(l), Object::Nil) => Object::String(format!("{}nil",l)),
-                        (Object::Nil, Object::String(r)) => Object::String(format!("nil{}",r)),
-                        (a, b) => panic!("{} {}", a, b)
-                    },
-                    TokenType::Slash => match (left, right) {
-                        (Object::Number(l), Object::Number(r)) => Object::Number(l/r),
-                        (_, _) => panic!()
-                    },
-                    TokenType::Star => match (left, right) {
-                        (Object::Number(l), Object::Number(r)) => Object::Number(l*r),
-                        (_, _) => panic!()
-                    },
-                    TokenType::Greater => match (left, right) {
-                        (Object::Number(l), Object::Number(r)) => Object::Boolean(l>r),
-                        (_, _) => panic!()
-                    },
-                    TokenType::GreaterEqual => match (left, right) {
-                        (Object::Number(l), Object::Number(r)) => Object::Boolean(l>=r),
-                        (_, _) => panic!()
-                    },
-                    TokenType::Less => match (left, right) {
-                        (Object::Number(l), Object::Number(r)) => Object::Boolean(l<r),
-                        (_, _) => panic!()
-                    },
-                    TokenType::LessEqual => match (left, right) {
-                        (Object::Number(l), Object::Number(r)) => Object::Boolean(l<=r),
-                        (_, _) => panic!()
-                    },
-                    TokenType::BangEqual => Object::Boolean(!(left == right)),
-                    TokenType::EqualEqual => Object::Boolean(left == right),
-                    _ => panic!()
+        let fields = class.borrow().fields.clone();
+        for field in fields {
+            let environment = Rc::new(RefCell::new(Environment::new(Some(field.closure.clone()))));
+            environment.borrow_mut().define(String::from("this"), instance.clone());
+            let enclosing = std::mem::replace(&mut self.environment, environment);
+            let value = self.visit_expr(&field.default);
+            self.environment = enclosing;
+            if let Object::Instance(instance) = instance {
+                instance.borrow_mut().define(field.name, value?);
+            }
+        }
+        Ok(())
+    }
+
+    /// Calls a method of an instance if its class defines it.
+    fn call_method(&mut self, receiver: &Object, method: &str, arguments: Vec<Object>, line: usize) -> Result<Option<Object>, Exception> {
+        if let Object::Instance(instance) = receiver {
+            let function = instance.borrow().class.borrow().find_method(method);
+            if let Some(Object::Function(function)) = function {
+                let function = function.borrow().bind(receiver.clone());
+                return self.call_function(Rc::new(RefCell::new(function)), arguments, line).map(Some);
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Calls the special method implementing an operator on an instance.
+    fn call_operator(&mut self, receiver: &Object, method: &str, arguments: Vec<Object>, op: &Token) -> Result<Object, Exception> {
+        match self.call_method(receiver, method, arguments, op.line)? {
+            Some(value) => Ok(value),
+            None => Err(self.error(op.line, format!("Operator '{}' is not supported by {}: define method '{}'.", op.lexeme, receiver, method)))
+        }
+    }
+
+    /// Converts a value to a string, using `toString()` for instances that define it.
+    fn stringify(&mut self, value: &Object, line: usize) -> Result<String, Exception> {
+        match value {
+            Object::Instance(_) => match self.call_method(value, "toString", Vec::new(), line)? {
+                Some(string) => Ok(string.to_string()),
+                None => Ok(value.to_string())
+            },
+            Object::List(list) => {
+                let mut elements = Vec::new();
+                for element in list.borrow().clone() {
+                    elements.push(self.stringify(&element, line)?);
                 }
+                Ok(format!("[{}]", elements.join(", ")))
             },
-            ExprType::Unary(op, expr) => {
-                let right = self.visit_expr(&expr);
-
-                match op.token_type {
-                    TokenType::Minus => match right {
-                            Object::Number(n) => Object::Number(-n),
-                            _ => panic!("{} Operator must be a number.", op.lexeme)
-                        },
-                    TokenType::Bang => Object::Boolean(!right.is_truthy()),
-                    _ => panic!()
+            Object::Map(map) => {
+                let mut entries = Vec::new();
+                for (key, value) in map.borrow().entries() {
+                    entries.push(format!("{}: {}", self.stringify(&key, line)?, self.stringify(&value, line)?));
                 }
+                Ok(format!("{{{}}}", entries.join(", ")))
             },
-            ExprType::Grouping(expr) => self.visit_expr(&expr),
-            ExprType::Literal(value) => value.clone(),
-            ExprType::Variable(name) => {
-                self.lookup_variable(name.lexeme.clone(), expr.id)
+            _ => Ok(value.to_string())
+        }
+    }
+
+    /// Checks whether a value is an instance of a class or of a class using a trait,
+    /// or a member of an enum.
+    fn is_instance(&mut self, value: &Object, class: &Object, line: usize) -> Result<bool, Exception> {
+        match class {
+            Object::Class(class) => match value {
+                Object::Instance(instance) => Ok(instance.borrow().class.borrow().is_subclass_of(class)),
+                _ => Ok(false)
             },
-            ExprType::Assign(name, value) => {
-                let value = self.visit_expr(&value);
-                let distance = self.locals.get(&expr.id);
+            Object::Trait(used) => match value {
+                Object::Instance(instance) => Ok(instance.borrow().class.borrow().uses_trait(used)),
+                _ => Ok(false)
+            },
+            Object::Enum(enumeration) => match value {
+                Object::EnumMember(member) => Ok(enumeration.contains(member)),
+                _ => Ok(false)
+            },
+            _ => Err(self.error(line, String::from("Right operand of 'is' must be a class, trait or enum.")))
+        }
+    }
 
-                match distance {
-                    Some(distance) => self.environment.borrow_mut().assign_at(*distance, name.lexeme.clone(), value.clone()),
-                    None => self.globals.borrow_mut().assign(name.lexeme.clone(), value.clone())
+    /// Compares two instances with `__eq` or `equals()` when one of them defines
+    /// one of those methods and falls back to identity otherwise. An instance never
+    /// equals nil or a value of another type.
+    fn equals(&mut self, left: &Object, right: &Object, line: usize) -> Result<bool, Exception> {
+        if let (Object::Instance(_), Object::Instance(_)) = (left, right) {
+            for (receiver, other) in [(left, right), (right, left)] {
+                for method in ["__eq", "equals"] {
+                    if let Some(equal) = self.call_method(receiver, method, vec![other.clone()], line)? {
+                        return Ok(equal.is_truthy());
+                    }
                 }
+            }
+        }
 
-                value
+        Ok(left == right)
+    }
+
+    /// Hashes a map key. Instances may define `hash()`, all other reference values
+    /// are hashed by identity.
+    fn hash(&mut self, key: &Object, line: usize) -> Result<u64, Exception> {
+        let mut hasher = DefaultHasher::new();
+        match key {
+            Object::Number(n) => {
+                let n = if *n == 0.0 { 0.0 } else { *n };
+                (0, n.to_bits()).hash(&mut hasher);
+            },
+            Object::String(s) => (1, s).hash(&mut hasher),
+            Object::Boolean(b) => (2, b).hash(&mut hasher),
+            Object::Nil => 3.hash(&mut hasher),
+            Object::NativeFunction(native) => (4, native.to_string()).hash(&mut hasher),
+            Object::Function(function) => (5, Rc::as_ptr(&function.borrow().body) as usize).hash(&mut hasher),
+            Object::Class(class) => (6, Rc::as_ptr(class) as usize).hash(&mut hasher),
+            Object::Map(map) => (7, Rc::as_ptr(map) as usize).hash(&mut hasher),
+            Object::List(list) => (11, Rc::as_ptr(list) as usize).hash(&mut hasher),
+            Object::Generator(generator) => (12, Rc::as_ptr(generator) as usize).hash(&mut hasher),
+            Object::Fiber(fiber) => (13, Rc::as_ptr(fiber) as usize).hash(&mut hasher),
+            Object::Trait(used) => (14, Rc::as_ptr(used) as usize).hash(&mut hasher),
+            Object::Enum(enumeration) => (15, Rc::as_ptr(enumeration) as usize).hash(&mut hasher),
+            Object::EnumMember(member) => (16, Rc::as_ptr(member) as usize).hash(&mut hasher),
+            Object::Range(range) => (17, range.start.to_bits(), range.end.to_bits(), range.step.to_bits(), range.inclusive).hash(&mut hasher),
+            Object::NativeMethod(receiver, name) => (8, self.hash(receiver, line)?, name).hash(&mut hasher),
+            Object::Instance(instance) => match self.call_method(key, "hash", Vec::new(), line)? {
+                Some(Object::Number(hash)) => (9, hash.to_bits()).hash(&mut hasher),
+                Some(_) => return Err(self.error(line, String::from("Method 'hash' must return a number."))),
+                None => (10, Rc::as_ptr(instance) as usize).hash(&mut hasher)
             },
-            ExprType::Logical(left, op, right) => {
-                let left = self.visit_expr(&left);
+        }
+        Ok(hasher.finish())
+    }
+
+    /// Looks up a key in a map, returning its hash and entry index if present.
+    fn find_key(&mut self, map: &Rc<RefCell<Map>>, key: &Object, line: usize) -> Result<(u64, Option<usize>), Exception> {
+        let hash = self.hash(key, line)?;
+        let candidates = map.borrow().candidates(hash);
+        for (index, candidate) in candidates {
+            if self.equals(key, &candidate, line)? {
+                return Ok((hash, Some(index)));
+            }
+        }
+        Ok((hash, None))
+    }
 
-                if op.token_type == TokenType::Or {
-                    if left.is_truthy() {
-                        return left;
+    /// Runs `body` for every element of a string, list, map (its keys), generator
+    /// or an instance implementing the iterator protocol, until the body returns.
+    fn iterate(&mut self, iterable: &Object, line: usize, mut body: impl FnMut(&mut Interpreter, Object) -> Result<(), Exception>) -> Result<(), Exception> {
+        match iterable {
+            Object::String(string) => {
+                for character in string.chars() {
+                    body(self, Object::String(character.to_string()))?;
+                    if self.returning {
+                        break;
                     }
-                } else {
-                    if !left.is_truthy() {
-                        return left;
+                }
+            },
+            Object::List(list) => {
+                let mut index = 0;
+                loop {
+                    let element = list.borrow().get(index).cloned();
+                    match element {
+                        Some(element) => body(self, element)?,
+                        None => break
+                    }
+                    if self.returning {
+                        break;
                     }
+                    index += 1;
                 }
-
-                self.visit_expr(&right)
             },
-            ExprType::Call(callee, paren, arguments) => {
-                let callee = self.visit_expr(&callee);
-
-                let mut args = Vec::new();
-                for argument in arguments {
-                    args.push(self.visit_expr(&argument));
+            Object::Map(map) => {
+                let keys: Vec<Object> = map.borrow().entries().into_iter().map(|(key, _)| key).collect();
+                for key in keys {
+                    body(self, key)?;
+                    if self.returning {
+                        break;
+                    }
+                }
+            },
+            Object::Range(range) => {
+                for value in range.values() {
+                    body(self, Object::Number(value))?;
+                    if self.returning {
+                        break;
+                    }
+                }
+            },
+            Object::Enum(enumeration) => {
+                for member in &enumeration.members {
+                    body(self, Object::EnumMember(member.clone()))?;
+                    if self.returning {
+                        break;
+                    }
+                }
+            },
+            Object::Generator(generator) => {
+                while let Some(value) = self.next_value(generator, line)? {
+                    body(self, value)?;
+                    if self.returning {
+                        break;
+                    }
+                }
+            },
+            Object::Instance(_) => {
+                let iterator = match self.call_method(iterable, "iterator", Vec::new(), line)? {
+                    Some(iterator) => iterator,
+                    None => iterable.clone()
+                };
+                if let Object::Generator(_) = iterator {
+                    return self.iterate(&iterator, line, body);
                 }
 
-                match callee {
-                    Object::Function(function) => self.call_function(function, args),
-                    Object::NativeFunction(native_function) => native_function.call(args),
-                    Object::Class(class) => {
-                        let instance = Object::Instance(Rc::new(RefCell::new(Instance::new(class.clone()))));
-                        if let Some(initializer) = class.borrow().find_method("init") {
-                            if let Object::Function(function) = initializer {
-                                self.call_function(Rc::new(RefCell::new(function.borrow_mut().bind(instance.clone()))), args);
-                            }
-                        }
-                        instance
+                loop {
+                    let has_next = match self.call_method(&iterator, "hasNext", Vec::new(), line)? {
+                        Some(has_next) => has_next,
+                        None => return Err(self.error(line, format!("{} is not iterable: define 'iterator()' or 'hasNext()' and 'next()'.", iterable)))
+                    };
+                    if !has_next.is_truthy() {
+                        break;
+                    }
+
+                    let value = match self.call_method(&iterator, "next", Vec::new(), line)? {
+                        Some(value) => value,
+                        None => return Err(self.error(line, format!("{} is not an iterator: define 'next()'.", iterator)))
+                    };
+                    body(self, value)?;
+                    if self.returning {
+                        break;
                     }
-                    _ => panic!("{} Can only call functions and classes.", paren.lexeme)
                 }
             },
-            ExprType::Get(expr, name) => {
-                let object = self.visit_expr(&expr);
-                if let Object::Instance(ref instance) = object {
-                    return instance.borrow().get(&name.lexeme, &object);
-                } else {
-                    panic!("Only instances have properties.");
+            _ => return Err(self.error(line, format!("{} is not iterable.", iterable)))
+        }
+        Ok(())
+    }
+
+    fn list_index(&mut self, list: &Rc<RefCell<Vec<Object>>>, index: &Object, line: usize) -> Result<usize, Exception> {
+        let length = list.borrow().len();
+        match index {
+            Object::Number(n) if n.fract() == 0.0 && *n >= 0.0 && (*n as usize) < length => Ok(*n as usize),
+            Object::Number(n) if n.fract() == 0.0 => Err(self.error(line, format!("List index {} out of range.", n))),
+            _ => Err(self.error(line, String::from("List index must be an integer.")))
+        }
+    }
+
+    fn call_native_method(&mut self, receiver: &Object, method: &str, arguments: Vec<Object>, line: usize) -> Result<Object, Exception> {
+        match (receiver, method) {
+            (Object::List(list), "push") => {
+                list.borrow_mut().extend(arguments);
+                Ok(Object::Nil)
+            },
+            (Object::List(list), "pop") => Ok(list.borrow_mut().pop().unwrap_or(Object::Nil)),
+            (Object::Generator(generator), "next") => Ok(self.next_value(generator, line)?.unwrap_or(Object::Nil)),
+            (Object::Generator(generator), "hasNext") => {
+                let value = self.next_value(generator, line)?;
+                let has_next = value.is_some();
+                generator.borrow_mut().buffered = value;
+                Ok(Object::Boolean(has_next))
+            },
+            (Object::Fiber(fiber), "call") => {
+                let value = arguments.into_iter().next().unwrap_or(Object::Nil);
+                self.run_fiber(fiber, value, line)
+            },
+            (Object::Fiber(fiber), "transfer") => {
+                let value = arguments.into_iter().next().unwrap_or(Object::Nil);
+                if !self.fiber {
+                    return self.run_fiber(fiber, value, line);
                 }
+                // Check the target here, so the error is raised in the transferring fiber.
+                let body = self.fiber_state(fiber, line)?;
+                fiber.borrow_mut().state = Some(body);
+                self.shared.borrow_mut().transfer = Some(fiber.clone());
+                Ok(self.suspend(value).unwrap_or(Object::Nil))
             },
-            ExprType::Set(object, name, value) => {
-                if let Object::Instance(instance) = self.visit_expr(object) {
-                    let value = self.visit_expr(value);
-                    instance.borrow_mut().set(name.lexeme.clone(), value.clone());
-                    return value;
-                } else {
-                    panic!("Only instances have fields.");
+            (Object::NativeFunction(NativeFunction::Fiber), "yield") => {
+                let value = arguments.into_iter().next().unwrap_or(Object::Nil);
+                if !self.fiber {
+                    return Err(self.error(line, String::from("Can only yield from inside a fiber.")));
                 }
+                Ok(self.suspend(value).unwrap_or(Object::Nil))
             },
-            ExprType::This(keyword) => {
-                return self.lookup_variable(keyword.lexeme.clone(), expr.id);
+            (Object::Range(range), "contains") => match arguments.first() {
+                Some(Object::Number(n)) => Ok(Object::Boolean(range.contains(*n))),
+                _ => Ok(Object::Boolean(false))
+            },
+            (Object::Map(map), "keys") => {
+                let keys = map.borrow().entries().into_iter().map(|(key, _)| key).collect();
+                Ok(Object::List(Rc::new(RefCell::new(keys))))
+            },
+            (Object::Map(map), "values") => {
+                let values = map.borrow().entries().into_iter().map(|(_, value)| value).collect();
+                Ok(Object::List(Rc::new(RefCell::new(values))))
+            },
+            (Object::Map(map), "has") => {
+                let key = arguments.into_iter().next().unwrap_or(Object::Nil);
+                let (_, index) = self.find_key(map, &key, line)?;
+                Ok(Object::Boolean(index.is_some()))
+            },
+            (Object::Map(map), "remove") => {
+                let key = arguments.into_iter().next().unwrap_or(Object::Nil);
+                match self.find_key(map, &key, line)? {
+                    (_, Some(index)) => Ok(map.borrow_mut().remove(index)),
+                    (_, None) => Ok(Object::Nil)
+                }
             },
-            ExprType::Super(_keyword, name) => {
-                let distance = *self.locals.get(&expr.id).unwrap();
+            _ => Err(self.error(line, format!("Undefined method '{}'.", method)))
+        }
+    }
 
-                let superclass = if let Object::Class(superclass) = self.environment.borrow().get_at(distance, &String::from("super")) {
-                    superclass
-                } else {
-                    panic!();
+    fn index(&mut self, object: &Object, bracket: &Token, index: Object) -> Result<Object, Exception> {
+        match object {
+            Object::Instance(_) => self.call_operator(object, "__index", vec![index], bracket),
+            Object::Map(map) => match self.find_key(map, &index, bracket.line)? {
+                (_, Some(index)) => Ok(map.borrow().value(index)),
+                (_, None) => Ok(Object::Nil)
+            },
+            Object::List(list) => {
+                if let Object::Range(range) = index {
+                    let length = list.borrow().len();
+                    let positions = self.slice_positions(&range, length, bracket.line)?;
+                    let elements = positions.into_iter().map(|position| list.borrow()[position].clone()).collect();
+                    return Ok(Object::List(Rc::new(RefCell::new(elements))));
+                }
+                let index = self.list_index(list, &index, bracket.line)?;
+                Ok(list.borrow()[index].clone())
+            },
+            Object::String(string) => {
+                if let Object::Range(range) = index {
+                    let characters: Vec<char> = string.chars().collect();
+                    let positions = self.slice_positions(&range, characters.len(), bracket.line)?;
+                    return Ok(Object::String(positions.into_iter().map(|position| characters[position]).collect()));
+                }
+                let character = match index {
+                    Object::Number(n) if n.fract() == 0.0 && n >= 0.0 => string.chars().nth(n as usize),
+                    _ => return Err(self.error(bracket.line, String::from("String index must be a non-negative integer.")))
                 };
+                match character {
+                    Some(character) => Ok(Object::String(character.to_string())),
+                    None => Err(self.error(bracket.line, String::from("String index out of range.")))
+                }
+            },
+            _ => Err(self.error(bracket.line, String::from("Only instances, lists, maps and strings can be indexed.")))
+        }
+    }
 
-                let object = self.environment.borrow().get_at(distance - 1, &String::from("this"));
-                
-                let method = if let Some(method) = superclass.borrow().find_method(&name.lexeme) {
-                    method
-                } else {
-                    panic!();
-                };
+    /// Checks that every value of a range used as a slice is a valid position.
+    fn slice_positions(&mut self, range: &Range, length: usize, line: usize) -> Result<Vec<usize>, Exception> {
+        let mut positions = Vec::new();
+        for position in range.values() {
+            if position.fract() != 0.0 {
+                return Err(self.error(line, String::from("Slice range must contain integers.")));
+            }
+            if position < 0.0 || position as usize >= length {
+                return Err(self.error(line, format!("Slice {} out of range.", range)));
+            }
+            positions.push(position as usize);
+        }
+        Ok(positions)
+    }
 
-                let function = if let Object::Function(function) = method {
-                    function
-                } else {
-                    panic!();
-                };
-                
-                return Object::Function(Rc::new(RefCell::new(function.borrow().bind(object))));
+    fn set_index(&mut self, object: &Object, bracket: &Token, index: Object, value: Object) -> Result<(), Exception> {
+        match object {
+            Object::Instance(_) => {
+                self.call_operator(object, "__setIndex", vec![index, value], bracket)?;
+            },
+            Object::Map(map) => match self.find_key(map, &index, bracket.line)? {
+                (_, Some(position)) => map.borrow_mut().replace(position, value),
+                (hash, None) => map.borrow_mut().insert(hash, index, value)
+            },
+            Object::List(list) => {
+                let index = self.list_index(list, &index, bracket.line)?;
+                list.borrow_mut()[index] = value;
             },
+            _ => return Err(self.error(bracket.line, String::from("Only instances, lists and maps support index assignment.")))
+        }
+        Ok(())
+    }
+
+    /// Matches a value against a pattern and collects the names it binds.
+    fn match_pattern(&mut self, pattern: &Pattern, value: &Object, bindings: &mut Vec<(String, Object)>, line: usize) -> Result<bool, Exception> {
+        match pattern {
+            Pattern::Wildcard => Ok(true),
+            Pattern::Binding(name) => {
+                bindings.push((name.lexeme.clone(), value.clone()));
+                Ok(true)
+            },
+            Pattern::Value(expected) => {
+                let expected = self.visit_expr(expected)?;
+                self.equals(value, &expected, line)
+            },
+            Pattern::Class(class, name) => {
+                let class = self.visit_expr(class)?;
+                if !self.is_instance(value, &class, name.line)? {
+                    return Ok(false);
+                }
+                bindings.push((name.lexeme.clone(), value.clone()));
+                Ok(true)
+            },
+            Pattern::List(elements, rest) => {
+                let list = match value {
+                    Object::List(list) => list.borrow().clone(),
+                    _ => return Ok(false)
+                };
+                if list.len() < elements.len() || (rest.is_none() && list.len() > elements.len()) {
+                    return Ok(false);
+                }
+                for (element, value) in elements.iter().zip(list.iter()) {
+                    if !self.match_pattern(element, value, bindings, line)? {
+                        return Ok(false);
+                    }
+                }
+                if let Some(rest) = rest {
+                    let remaining = list[elements.len()..].to_vec();
+                    bindings.push((rest.lexeme.clone(), Object::List(Rc::new(RefCell::new(remaining)))));
+                }
+                Ok(true)
+            }
+        }
+    }
+
+    /// Runs the body of the first case with a matching pattern and guard in an
+    /// environment holding the case's bindings.
+    fn execute_match(&mut self, cases: &[Case], value: &Object, line: usize) -> Result<(), Exception> {
+        for case in cases {
+            for pattern in &case.patterns {
+                let mut bindings = Vec::new();
+                if !self.match_pattern(pattern, value, &mut bindings, line)? {
+                    continue;
+                }
+
+                let environment = Rc::new(RefCell::new(Environment::new(Some(self.environment.clone()))));
+                for name in case.patterns.iter().flat_map(|pattern| pattern.bindings()) {
+                    environment.borrow_mut().define(name.lexeme.clone(), Object::Nil);
+                }
+                for (name, value) in bindings {
+                    environment.borrow_mut().define(name, value);
+                }
+
+                if let Some(guard) = &case.guard {
+                    let enclosing = std::mem::replace(&mut self.environment, environment.clone());
+                    let passed = self.visit_expr(guard);
+                    self.environment = enclosing;
+                    if !passed?.is_truthy() {
+                        continue;
+                    }
+                }
+
+                return self.execute_block(std::slice::from_ref(&case.body), environment);
+            }
+        }
+        Ok(())
+    }
+
+    /// Assigns a value to a variable, property or index target.
+    fn assign_target(&mut self, target: &Expr, value: Object) -> Result<(), Exception> {
+        match &target.expr_type {
+            ExprType::Variable(name) => self.assign_variable(name, target.id, value),
+            ExprType::Get(object, name) => {
+                let object = self.visit_expr(object)?;
+                self.set_property(&object, name, value)
+            },
+            ExprType::Index(object, bracket, index) => {
+                let object = self.visit_expr(object)?;
+                let index = self.visit_expr(index)?;
+                self.set_index(&object, bracket, index, value)
+            },
+            _ => panic!()
+        }
+    }
+
+    /// Takes the elements of a list for a destructuring assignment or declaration.
+    /// Missing elements are nil and the remaining ones are returned separately.
+    fn destructure_list(&mut self, value: &Object, count: usize, line: usize) -> Result<(Vec<Object>, Vec<Object>), Exception> {
+        match value {
+            Object::List(list) => {
+                let list = list.borrow();
+                let mut elements: Vec<Object> = list.iter().take(count).cloned().collect();
+                elements.resize(count, Object::Nil);
+                Ok((elements, list.iter().skip(count).cloned().collect()))
+            },
+            _ => Err(self.error(line, String::from("Only lists can be destructured with '[...]'.")))
+        }
+    }
+
+    /// Looks up a map entry or an instance property by name for destructuring.
+    fn destructure_field(&mut self, value: &Object, name: &Token) -> Result<Object, Exception> {
+        match value {
+            Object::Map(map) => match self.find_key(map, &Object::String(name.lexeme.clone()), name.line)? {
+                (_, Some(index)) => Ok(map.borrow().value(index)),
+                (_, None) => Ok(Object::Nil)
+            },
+            Object::Instance(instance) => {
+                if instance.borrow().get(&name.lexeme, value).is_err() {
+                    return Ok(Object::Nil);
+                }
+                self.get_property(value, name)
+            },
+            _ => Err(self.error(name.line, String::from("Only maps and instances can be destructured with '{...}'.")))
+        }
+    }
+
+    fn integer_operands(&mut self, left: &Object, right: &Object, op: &Token) -> Result<(i64, i64), Exception> {
+        match (left, right) {
+            (Object::Number(l), Object::Number(r)) if l.fract() == 0.0 && r.fract() == 0.0 => Ok((*l as i64, *r as i64)),
+            (_, _) => Err(self.error(op.line, String::from("Operands must be integers.")))
+        }
+    }
+
+    fn assign_variable(&mut self, name: &Token, id: usize, value: Object) -> Result<(), Exception> {
+        let distance = self.shared.borrow().locals.get(&id).copied();
+        let result = match distance {
+            Some(distance) => {
+                self.environment.borrow_mut().assign_at(distance, name.lexeme.clone(), value);
+                Ok(())
+            },
+            None => Environment::root(&self.environment).borrow_mut().assign(name.lexeme.clone(), value)
+        };
+        result.map_err(|message| self.error(name.line, message))
+    }
+
+    fn get_property(&mut self, object: &Object, name: &Token) -> Result<Object, Exception> {
+        if let Object::Map(map) = object {
+            return match name.lexeme.as_str() {
+                "size" => Ok(Object::Number(map.borrow().len() as f64)),
+                "has" | "remove" | "keys" | "values" => Ok(Object::NativeMethod(Box::from(object.clone()), name.lexeme.clone())),
+                _ => Err(self.error(name.line, format!("Undefined property '{}'.", name.lexeme)))
+            };
+        }
+
+        if let Object::List(list) = object {
+            return match name.lexeme.as_str() {
+                "length" => Ok(Object::Number(list.borrow().len() as f64)),
+                "push" | "pop" => Ok(Object::NativeMethod(Box::from(object.clone()), name.lexeme.clone())),
+                _ => Err(self.error(name.line, format!("Undefined property '{}'.", name.lexeme)))
+            };
+        }
+
+        if let Object::Fiber(fiber) = object {
+            return match name.lexeme.as_str() {
+                "isDone" => Ok(Object::Boolean(fiber.borrow().done)),
+                "call" | "transfer" => Ok(Object::NativeMethod(Box::from(object.clone()), name.lexeme.clone())),
+                _ => Err(self.error(name.line, format!("Undefined property '{}'.", name.lexeme)))
+            };
+        }
+
+        if let Object::NativeFunction(NativeFunction::Fiber) = object {
+            return match name.lexeme.as_str() {
+                "yield" => Ok(Object::NativeMethod(Box::from(object.clone()), name.lexeme.clone())),
+                _ => Err(self.error(name.line, format!("Undefined property '{}'.", name.lexeme)))
+            };
+        }
+
+        if let Object::Enum(enumeration) = object {
+            return match enumeration.member(&name.lexeme) {
+                Some(member) => Ok(Object::EnumMember(member)),
+                None => Err(self.error(name.line, format!("Enum '{}' has no member '{}'.", enumeration.name, name.lexeme)))
+            };
+        }
+
+        if let Object::Range(range) = object {
+            return match name.lexeme.as_str() {
+                "length" => Ok(Object::Number(range.len() as f64)),
+                "start" => Ok(Object::Number(range.start)),
+                "end" => Ok(Object::Number(range.end)),
+                "step" => Ok(Object::Number(range.step)),
+                "contains" => Ok(Object::NativeMethod(Box::from(object.clone()), name.lexeme.clone())),
+                _ => Err(self.error(name.line, format!("Undefined property '{}'.", name.lexeme)))
+            };
+        }
+
+        if let Object::EnumMember(member) = object {
+            return match name.lexeme.as_str() {
+                "name" => Ok(Object::String(member.name.clone())),
+                "ordinal" => Ok(Object::Number(member.ordinal as f64)),
+                _ => Err(self.error(name.line, format!("Undefined property '{}'.", name.lexeme)))
+            };
+        }
+
+        if let Object::Generator(_) = object {
+            return match name.lexeme.as_str() {
+                "next" | "hasNext" => Ok(Object::NativeMethod(Box::from(object.clone()), name.lexeme.clone())),
+                _ => Err(self.error(name.line, format!("Undefined property '{}'.", name.lexeme)))
+            };
+        }
+
+        if let Object::Instance(instance) = object {
+            let property = instance.borrow().get(&name.lexeme, object);
+            let property = property.map_err(|message| self.error(name.line, message))?;
+            if let Object::Function(ref function) = property {
+                if function.borrow().kind == FunctionKind::Getter {
+                    return self.call_function(function.clone(), Vec::new(), name.line);
+                }
+            }
+            Ok(property)
+        } else {
+            Err(self.error(name.line, String::from("Only instances have properties.")))
+        }
+    }
+
+    fn set_property(&mut self, object: &Object, name: &Token, value: Object) -> Result<(), Exception> {
+        if let Object::Instance(instance) = object {
+            let setter = instance.borrow_mut().set(name.lexeme.clone(), value.clone(), object);
+            if let Some(setter) = setter {
+                self.call_function(Rc::new(RefCell::new(setter)), vec![value], name.line)?;
+            }
+            Ok(())
+        } else {
+            Err(self.error(name.line, String::from("Only instances have fields.")))
+        }
+    }
+
+    /// Loads, runs and caches a module. Every module runs in its own global
+    /// environment and only the declarations it exports are visible to importers.
+    fn import_module(&mut self, path: &Token) -> Result<Rc<Exports>, Exception> {
+        let resolved = {
+            let shared = self.shared.borrow();
+            resolve_path(&path.lexeme, shared.loading.last(), &shared.search_path)
+        };
+        let module_path = match resolved {
+            Some(module_path) => module_path,
+            None => return Err(self.error(path.line, format!("Could not find module '{}'.", path.lexeme)))
+        };
+
+        let cached = self.shared.borrow().modules.get(&module_path).cloned();
+        if let Some(module) = cached {
+            return Ok(module);
+        }
+
+        let loading = self.shared.borrow().loading.clone();
+        if loading.contains(&module_path) {
+            let cycle: Vec<String> = loading.iter()
+                .skip_while(|loading| **loading != module_path)
+                .chain(std::iter::once(&module_path))
+                .map(|loading| loading.display().to_string())
+                .collect();
+            return Err(self.error(path.line, format!("Import cycle detected: {}.", cycle.join(" -> "))));
+        }
+
+        let source = match read_to_string(&module_path) {
+            Ok(source) => source,
+            Err(e) => return Err(self.error(path.line, format!("Could not read module '{}': {}", path.lexeme, e)))
+        };
+
+        let statements = parse(scan_tokens(source));
+        self.extend_locals(resolve(&statements));
+        let statements = optimize(statements);
+
+        let module_environment = Rc::new(RefCell::new(self.shared.borrow().builtins.clone()));
+        let enclosing = std::mem::replace(&mut self.environment, module_environment.clone());
+        self.shared.borrow_mut().loading.push(module_path.clone());
+        self.frames.push((format!("module \"{}\"", path.lexeme), path.line));
+
+        let result = self.interpret(&statements);
+
+        self.frames.pop();
+        self.shared.borrow_mut().loading.pop();
+        self.environment = enclosing;
+        result?;
+
+        let module = Rc::new(exports(&statements, |name| module_environment.borrow().get(name).ok()));
+        self.shared.borrow_mut().modules.insert(module_path, module.clone());
+        Ok(module)
+    }
+ 
+    fn evaluate_all(&mut self, exprs: &[Expr]) -> Result<Vec<Object>, Exception> {
+        let mut values = Vec::new();
+        for expr in exprs {
+            values.push(self.visit_expr(expr)?);
+        }
+        Ok(values)
+    }
+
+    #[inline(never)]
+    fn define_destructured(&mut self, destructure: &Destructure, initializer: &Expr) -> Result<(), Exception> {
+        let value = self.visit_expr(initializer)?;
+        match destructure {
+            Destructure::List(names, rest) => {
+                let line = names.iter().chain(rest.iter()).next().map_or(0, |name| name.line);
+                let (elements, remaining) = self.destructure_list(&value, names.len(), line)?;
+                for (name, element) in names.iter().zip(elements) {
+                    self.environment.borrow_mut().define(name.lexeme.clone(), element);
+                }
+                if let Some(rest) = rest {
+                    self.environment.borrow_mut().define(rest.lexeme.clone(), Object::List(Rc::new(RefCell::new(remaining))));
+                }
+            },
+            Destructure::Fields(names) => {
+                for name in names {
+                    let field = self.destructure_field(&value, name)?;
+                    self.environment.borrow_mut().define(name.lexeme.clone(), field);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    #[inline(never)]
+    fn execute_for_in(&mut self, name: &Token, iterable: &Expr, body: &Stmt) -> Result<(), Exception> {
+        let iterable = self.visit_expr(iterable)?;
+        self.iterate(&iterable, name.line, |interpreter, value| {
+            let e = Rc::new(RefCell::new(Environment::new(Some(interpreter.environment.clone()))));
+            e.borrow_mut().define(name.lexeme.clone(), value);
+            interpreter.execute_block(std::slice::from_ref(body), e)
+        })
+    }
+
+    #[inline(never)]
+    fn execute_assign(&mut self, targets: &[Expr], equals: &Token, values: &[Expr]) -> Result<(), Exception> {
+        // All values are evaluated before anything is assigned, so `a, b = b, a;` swaps.
+        let mut evaluated = self.evaluate_all(values)?;
+        if evaluated.len() == 1 && targets.len() > 1 {
+            (evaluated, _) = self.destructure_list(&evaluated[0], targets.len(), equals.line)?;
+        }
+        for (target, value) in targets.iter().zip(evaluated) {
+            self.assign_target(target, value)?;
+        }
+        Ok(())
+    }
+
+    #[inline(never)]
+    fn execute_import(&mut self, path: &Token, names: &Option<Vec<Token>>) -> Result<(), Exception> {
+        let module = self.import_module(path)?;
+        let imports: Vec<(&String, &(Object, bool))> = match names {
+            Some(names) => {
+                let mut imports = Vec::new();
+                for name in names {
+                    match module.get_key_value(&name.lexeme) {
+                        Some(import) => imports.push(import),
+                        None => return Err(self.error(name.line, format!("Module '{}' does not export '{}'.", path.lexeme, name.lexeme)))
+                    }
+                }
+                imports
+            },
+            None => module.iter().collect()
+        };
+
+        // Constants stay constant in the importing module.
+        for (name, (value, constant)) in imports {
+            if *constant {
+                self.environment.borrow_mut().define_constant(name.clone(), value.clone());
+            } else {
+                self.environment.borrow_mut().define(name.clone(), value.clone());
+            }
+        }
+        Ok(())
+    }
+
+    #[inline(never)]
+    fn execute_try(&mut self, body: &[Stmt], catch_clause: &Option<(Token, Vec<Stmt>)>, finally_clause: &Option<Vec<Stmt>>) -> Result<(), Exception> {
+        let e = Rc::new(RefCell::new(Environment::new(Some(self.environment.clone()))));
+        let mut result = self.execute_block(body, e);
+
+        if let (Err(exception), Some((name, catch_body))) = (&result, catch_clause.as_ref()) {
+            let e = Rc::new(RefCell::new(Environment::new(Some(self.environment.clone()))));
+            e.borrow_mut().define(name.lexeme.clone(), exception.value.clone());
+            result = self.execute_block(catch_body, e);
+        }
+
+        if let Some(finally_body) = finally_clause.as_ref() {
+            // A pending return must survive the finally block, unless it returns itself.
+            let returning = self.returning;
+            let return_value = self.globals.borrow_mut().get_return_value();
+            self.returning = false;
+
+            let e = Rc::new(RefCell::new(Environment::new(Some(self.environment.clone()))));
+            self.execute_block(finally_body, e)?;
+
+            if self.returning {
+                return Ok(());
+            }
+
+            self.returning = returning;
+            self.globals.borrow_mut().define("return_value".to_string(), return_value);
+        }
+
+        result
+    }
+
+    #[inline(never)]
+    fn declare_function(&mut self, name: &Token, parameters: &Rc<Vec<Parameter>>, body: &Rc<Vec<Stmt>>, generator: bool) {
+        let function = Function::new(name.lexeme.clone(), parameters.clone(), body.clone(), self.environment.clone(), FunctionKind::Function, generator);
+        self.environment.borrow_mut().define(name.lexeme.clone(), Object::Function(Rc::new(RefCell::new(function))));
+    }
+
+    #[inline(never)]
+    fn declare_enum(&mut self, name: &Token, members: &[Token]) {
+        let members = members.iter().map(|member| member.lexeme.clone()).collect();
+        let enumeration = Enum::new(name.lexeme.clone(), members);
+        self.environment.borrow_mut().define(name.lexeme.clone(), Object::Enum(enumeration));
+    }
+
+    #[inline(never)]
+    fn declare_trait(&mut self, name: &Token, methods: &Vec<Stmt>, getters: &Vec<Stmt>, setters: &Vec<Stmt>) {
+        let used = Trait {
+            name: name.lexeme.clone(),
+            methods: self.accessors(methods, FunctionKind::Method),
+            getters: self.accessors(getters, FunctionKind::Getter),
+            setters: self.accessors(setters, FunctionKind::Setter)
+        };
+        self.environment.borrow_mut().define(name.lexeme.clone(), Object::Trait(Rc::new(used)));
+    }
+
+    #[inline(never)]
+    fn declare_class(&mut self, stmt: &Stmt) -> Result<(), Exception> {
+        let (name, superclass, traits, methods, getters, setters, fields, abstracts) = match stmt {
+            Stmt::Class(name, superclass, traits, methods, getters, setters, fields, abstracts) =>
+                (name, superclass, traits, methods, getters, setters, fields, abstracts),
+            _ => panic!()
+        };
+
+        let superclass = if let Some(superclass) = superclass.as_ref() {
+            if let Object::Class(class) = self.visit_expr(superclass)? {
+                Some(class)
+            } else {
+                return Err(self.error(name.line, String::from("Superclass must be a class.")));
+            }
+        } else {
+            None
+        };
+
+        let mut used_traits = Vec::new();
+        for used in traits {
+            match self.visit_expr(used)? {
+                Object::Trait(used) => used_traits.push(used),
+                _ => {
+                    let used = if let ExprType::Variable(used) = &used.expr_type { used.lexeme.clone() } else { panic!() };
+                    return Err(self.error(name.line, format!("'{}' is not a trait.", used)));
+                }
+            }
+        }
+
+        self.environment.borrow_mut().define(name.lexeme.clone(), Object::Nil);
+
+        if let Some(sc) = superclass.as_ref() {
+            self.environment = Rc::new(RefCell::new(Environment::new(Some(self.environment.clone()))));
+            self.environment.borrow_mut().define(String::from("super"), Object::Class(sc.clone()));
+        }
+
+        let mut methods2 = HashMap::new();
+        for method in methods {
+            if let Stmt::Function(name, parameters, body, generator, _) = method {
+                let kind = if name.lexeme == "init" { FunctionKind::Initializer } else { FunctionKind::Method };
+                let function = Function::new(name.lexeme.clone(), parameters.clone(), body.clone(), self.environment.clone(),
+                    kind, *generator);
+                methods2.insert(name.lexeme.clone(), Object::Function(Rc::new(RefCell::new(function))));
+            } else {
+                panic!();
+            }
+        }
+
+        let mut getters = self.accessors(getters, FunctionKind::Getter);
+        let mut setters = self.accessors(setters, FunctionKind::Setter);
+
+        let fields = fields.iter().map(|field| match field {
+            Stmt::Var(name, default, _) => Field {
+                name: name.lexeme.clone(),
+                default: default.as_ref().clone(),
+                closure: self.environment.clone()
+            },
+            _ => panic!()
+        }).collect();
+
+        if superclass.is_some() {
+            let parent = self.environment.borrow().parent.clone().unwrap();
+            self.environment = parent;
+        }
+
+        self.use_traits(name, &used_traits, &mut methods2, |used| &used.methods)?;
+        self.use_traits(name, &used_traits, &mut getters, |used| &used.getters)?;
+        self.use_traits(name, &used_traits, &mut setters, |used| &used.setters)?;
+
+        let mut abstract_methods = Vec::new();
+        for method in abstracts {
+            if let Stmt::Function(method, _, _, _, _) = method {
+                abstract_methods.push(method.lexeme.clone());
+            }
+        }
+        if let Some(superclass) = superclass.as_ref() {
+            for method in superclass.borrow().abstracts.iter() {
+                if !methods2.contains_key(method) && !abstract_methods.contains(method) {
+                    return Err(self.error(name.line, format!("Class '{}' must implement abstract method '{}' of '{}'.",
+                        name.lexeme, method, superclass.borrow().name)));
+                }
+            }
+        }
+
+        let mut class = Class::new(name.lexeme.clone(), superclass, used_traits, methods2, getters, setters);
+        class.fields = fields;
+        class.abstracts = abstract_methods;
+        let class = Rc::new(RefCell::new(class));
+
+        self.environment.borrow_mut().define(name.lexeme.clone(), Object::Class(class));
+        Ok(())
+    }
+
+    #[inline(never)]
+    fn call_native(&mut self, function: NativeFunction, arguments: Vec<Object>, line: usize) -> Result<Object, Exception> {
+        function.call(arguments).map_err(|message| self.error(line, message))
+    }
+
+    #[inline(never)]
+    fn instantiate(&mut self, class: Rc<RefCell<Class>>, arguments: Vec<Object>, line: usize) -> Result<Object, Exception> {
+        if !class.borrow().abstracts.is_empty() {
+            let class = class.borrow();
+            return Err(self.error(line, format!("Can't instantiate abstract class '{}', it doesn't implement '{}'.",
+                class.name, class.abstracts.join("', '"))));
+        }
+        let instance = Object::Instance(Rc::new(RefCell::new(Instance::new(class.clone()))));
+        self.initialize_fields(&class, &instance)?;
+        let initializer = class.borrow().find_method("init");
+        if let Some(Object::Function(function)) = initializer {
+            let initializer = function.borrow().bind(instance.clone());
+            self.call_function(Rc::new(RefCell::new(initializer)), arguments, line)?;
+        }
+        Ok(instance)
+    }
+
+    #[inline(never)]
+    fn unary(&mut self, op: &Token, expr: &Expr) -> Result<Object, Exception> {
+        let right = self.visit_expr(expr)?;
+
+        let value = match op.token_type {
+            TokenType::Minus => match right {
+                    Object::Number(n) => Object::Number(-n),
+                    Object::Instance(_) => return self.call_operator(&right, "__neg", Vec::new(), op),
+                    _ => return Err(self.error(op.line, String::from("Operand must be a number.")))
+                },
+            TokenType::Bang => Object::Boolean(!right.is_truthy()),
+            TokenType::TypeOf => Object::String(String::from(right.type_name())),
+            TokenType::Tilde => match right {
+                Object::Number(n) if n.fract() == 0.0 => Object::Number(!(n as i64) as f64),
+                _ => return Err(self.error(op.line, String::from("Operand must be an integer.")))
+            },
+            _ => panic!()
+        };
+        Ok(value)
+    }
+
+    #[inline(never)]
+    fn logical(&mut self, left: &Expr, op: &Token, right: &Expr) -> Result<Object, Exception> {
+        let left = self.visit_expr(left)?;
+
+        if op.token_type == TokenType::Or {
+            if left.is_truthy() {
+                return Ok(left);
+            }
+        } else if op.token_type == TokenType::QuestionQuestion {
+            if !matches!(left, Object::Nil) {
+                return Ok(left);
+            }
+        } else {
+            if !left.is_truthy() {
+                return Ok(left);
+            }
+        }
+
+        self.visit_expr(right)
+    }
+
+    #[inline(never)]
+    fn compound_assign(&mut self, name: &Token, id: usize, op: &Token, value: &Expr, postfix: bool) -> Result<Object, Exception> {
+        let current = self.lookup_variable(name, id)?;
+        let operand = self.visit_expr(value)?;
+        let value = self.binary(current.clone(), op, operand)?;
+        self.assign_variable(name, id, value.clone())?;
+        Ok(if postfix { current } else { value })
+    }
+
+    #[inline(never)]
+    fn range(&mut self, start: &Expr, operator: &Token, end: &Expr, step: &Option<Box<Expr>>) -> Result<Object, Exception> {
+        let start = self.visit_expr(start)?;
+        let end = self.visit_expr(end)?;
+        let step = match step {
+            Some(step) => self.visit_expr(step)?,
+            None => Object::Number(1.0)
+        };
+        match (start, end, step) {
+            (Object::Number(_), Object::Number(_), Object::Number(0.0)) => {
+                Err(self.error(operator.line, String::from("Range step can't be zero.")))
+            },
+            (Object::Number(start), Object::Number(end), Object::Number(step)) => {
+                Ok(Object::Range(Range { start, end, step, inclusive: operator.token_type == TokenType::DotDotEqual }))
+            },
+            _ => Err(self.error(operator.line, String::from("Range bounds and step must be numbers.")))
+        }
+    }
+
+    #[inline(never)]
+    fn assign_index(&mut self, object: &Expr, bracket: &Token, index: &Expr, value: &Expr) -> Result<Object, Exception> {
+        let object = self.visit_expr(object)?;
+        let index = self.visit_expr(index)?;
+        let value = self.visit_expr(value)?;
+        self.set_index(&object, bracket, index, value.clone())?;
+        Ok(value)
+    }
+
+    #[inline(never)]
+    fn compound_set_index(&mut self, object: &Expr, bracket: &Token, index: &Expr, op: &Token, value: &Expr, postfix: bool) -> Result<Object, Exception> {
+        let object = self.visit_expr(object)?;
+        let index = self.visit_expr(index)?;
+        let current = self.index(&object, bracket, index.clone())?;
+        let operand = self.visit_expr(value)?;
+        let value = self.binary(current.clone(), op, operand)?;
+        self.set_index(&object, bracket, index, value.clone())?;
+        Ok(if postfix { current } else { value })
+    }
+
+    #[inline(never)]
+    fn assign_property(&mut self, object: &Expr, name: &Token, value: &Expr) -> Result<Object, Exception> {
+        let object = self.visit_expr(object)?;
+        let value = self.visit_expr(value)?;
+        self.set_property(&object, name, value.clone())?;
+        Ok(value)
+    }
+
+    #[inline(never)]
+    fn compound_set(&mut self, object: &Expr, name: &Token, op: &Token, value: &Expr, postfix: bool) -> Result<Object, Exception> {
+        let object = self.visit_expr(object)?;
+        let current = self.get_property(&object, name)?;
+        let operand = self.visit_expr(value)?;
+        let value = self.binary(current.clone(), op, operand)?;
+        self.set_property(&object, name, value.clone())?;
+        Ok(if postfix { current } else { value })
+    }
+
+    #[inline(never)]
+    fn super_method(&mut self, keyword: &Token, name: &Token, id: usize) -> Result<Object, Exception> {
+        let distance = *self.shared.borrow().locals.get(&id).unwrap();
+
+        let superclass = if let Object::Class(superclass) = self.environment.borrow().get_at(distance, &String::from("super")) {
+            superclass
+        } else {
+            panic!();
+        };
+
+        let object = self.environment.borrow().get_at(distance - 1, &String::from("this"));
+        
+        let method = if let Some(method) = superclass.borrow().find_method(&name.lexeme) {
+            method
+        } else {
+            return Err(self.error(keyword.line, format!("Undefined property '{}'.", name.lexeme)));
+        };
+
+        let function = if let Object::Function(function) = method {
+            function
+        } else {
+            panic!();
+        };
+        
+        return Ok(Object::Function(Rc::new(RefCell::new(function.borrow().bind(object)))));
+    }
+
+}
+
+impl Visitor<Result<Object, Exception>, Result<(), Exception>> for Interpreter {
+
+    fn visit_stmt(&mut self, stmt: &Stmt) -> Result<(), Exception> {
+        if self.returning {
+            return Ok(());
+        }
+
+        match stmt {
+            Stmt::Expression(expr) => {self.visit_expr(expr)?;},
+            Stmt::Print(keyword, expr) => {
+                let value = self.visit_expr(expr)?;
+                println!("{}", self.stringify(&value, keyword.line)?);
+            },
+            Stmt::Var(name, initializer, _) => {
+                let value = self.visit_expr(initializer)?;
+                self.environment.borrow_mut().define(name.lexeme.clone(), value);
+            },
+            Stmt::VarDestructure(destructure, initializer) => self.define_destructured(destructure, initializer)?,
+            Stmt::Assign(targets, equals, values) => self.execute_assign(targets, equals, values)?,
+            Stmt::Const(name, initializer, _) => {
+                let value = self.visit_expr(initializer)?;
+                self.environment.borrow_mut().define_constant(name.lexeme.clone(), value);
+            },
+            Stmt::Block(statements) => {
+                let e = Rc::new(RefCell::new(Environment::new(Some(self.environment.clone()))));
+                self.execute_block(statements, e)?;
+            },
+            Stmt::If(condition, then_branch, else_branch) => {
+                if self.visit_expr(condition)?.is_truthy() {
+                    self.visit_stmt(then_branch)?;
+                } else {
+                    if let Some(statement) = else_branch.as_ref() {
+                        self.visit_stmt(statement)?;
+                    }
+                }
+            },
+            Stmt::While(_keyword, condition, body) => {
+                while !self.returning && self.visit_expr(condition)?.is_truthy() {
+                    self.visit_stmt(body)?;
+                }
+            }
+            Stmt::Match(keyword, subject, cases) => {
+                let value = self.visit_expr(subject)?;
+                self.execute_match(cases, &value, keyword.line)?;
+            },
+            Stmt::ForIn(name, iterable, body) => self.execute_for_in(name, iterable, body)?,
+            Stmt::Function(name, parameters, body, generator, _) => self.declare_function(name, parameters, body, *generator),
+            Stmt::Return(_keyword, value) => {
+                
+                match value.as_ref() {
+                    Some(value) => {
+                        let return_value = self.visit_expr(value)?;
+                        self.returning = true;
+                        self.globals.borrow_mut().define("return_value".to_string(), return_value);
+                    },
+                    None => {
+                        self.returning = true;
+                        self.globals.borrow_mut().define("return_value".to_string(), Object::Nil);
+                    }
+                }
+            },
+            Stmt::Import(path, names) => self.execute_import(path, names)?,
+            Stmt::Export(declaration) => self.visit_stmt(declaration)?,
+            Stmt::Throw(keyword, value) => {
+                let value = self.visit_expr(value)?;
+                return Err(self.throw(value, keyword.line));
+            },
+            Stmt::Yield(keyword, value) => {
+                let value = self.visit_expr(value)?;
+                if self.suspend(value).is_none() {
+                    return Err(self.error(keyword.line, String::from("Can only yield inside a generator.")));
+                }
+            },
+            Stmt::Try(body, catch_clause, finally_clause) => self.execute_try(body, catch_clause, finally_clause)?,
+            Stmt::Enum(name, members) => self.declare_enum(name, members),
+            Stmt::Trait(name, methods, getters, setters) => self.declare_trait(name, methods, getters, setters),
+            Stmt::Class(..) => self.declare_class(stmt)?,
+        };
+        Ok(())
+    }
+
+    fn visit_expr(&mut self, expr: &Expr) -> Result<Object, Exception> {
+        match &expr.expr_type {
+            ExprType::Binary(left, op, right) => {
+                let left = self.visit_expr(&left)?;
+                let right = self.visit_expr(&right)?;
+
+                self.binary(left, op, right)
+            },
+            ExprType::Unary(op, expr) => self.unary(op, expr),
+            ExprType::Grouping(expr) => self.visit_expr(&expr),
+            ExprType::Literal(value) => Ok(value.clone()),
+            ExprType::List(elements) => {
+                let list = self.evaluate_all(elements)?;
+                Ok(Object::List(Rc::new(RefCell::new(list))))
+            },
+            ExprType::Variable(name) => {
+                self.lookup_variable(name, expr.id)
+            },
+            ExprType::Assign(name, value) => {
+                let value = self.visit_expr(&value)?;
+                self.assign_variable(name, expr.id, value.clone())?;
+                Ok(value)
+            },
+            ExprType::CompoundAssign(name, op, value, postfix) => self.compound_assign(name, expr.id, op, value, *postfix),
+            ExprType::Logical(left, op, right) => self.logical(left, op, right),
+            ExprType::Conditional(condition, then_branch, else_branch) => {
+                if self.visit_expr(condition)?.is_truthy() {
+                    self.visit_expr(then_branch)
+                } else {
+                    self.visit_expr(else_branch)
+                }
+            },
+            ExprType::Call(callee, paren, arguments) => {
+                let callee = self.visit_expr(&callee)?;
+                if self.short_circuit {
+                    return Ok(Object::Nil);
+                }
+
+                let args = self.evaluate_all(arguments)?;
+                match callee {
+                    Object::Function(function) => self.call_function(function, args, paren.line),
+                    Object::NativeFunction(native_function) => self.call_native(native_function, args, paren.line),
+                    Object::NativeMethod(receiver, method) => self.call_native_method(&receiver, &method, args, paren.line),
+                    Object::Class(class) => self.instantiate(class, args, paren.line),
+                    _ => Err(self.error(paren.line, String::from("Can only call functions and classes.")))
+                }
+            },
+            ExprType::Get(expr, name) => {
+                let object = self.visit_expr(&expr)?;
+                if self.short_circuit {
+                    return Ok(Object::Nil);
+                }
+                self.get_property(&object, name)
+            },
+            ExprType::Range(start, operator, end, step) => self.range(start, operator, end, step),
+            ExprType::Index(object, bracket, index) => {
+                let object = self.visit_expr(object)?;
+                if self.short_circuit {
+                    return Ok(Object::Nil);
+                }
+                let index = self.visit_expr(index)?;
+                self.index(&object, bracket, index)
+            },
+            ExprType::SetIndex(object, bracket, index, value) => self.assign_index(object, bracket, index, value),
+            ExprType::CompoundSetIndex(object, bracket, index, op, value, postfix) => self.compound_set_index(object, bracket, index, op, value, *postfix),
+            ExprType::OptionalGet(expr, name) => {
+                let object = self.visit_expr(expr)?;
+                if self.short_circuit || matches!(object, Object::Nil) {
+                    self.short_circuit = true;
+                    return Ok(Object::Nil);
+                }
+                self.get_property(&object, name)
+            },
+            ExprType::OptionalChain(expr) => {
+                let value = self.visit_expr(expr);
+                self.short_circuit = false;
+                value
+            },
+            ExprType::Set(object, name, value) => self.assign_property(object, name, value),
+            ExprType::CompoundSet(object, name, op, value, postfix) => self.compound_set(object, name, op, value, *postfix),
+            ExprType::This(keyword) => {
+                return self.lookup_variable(keyword, expr.id);
+            },
+            ExprType::Super(keyword, name) => self.super_method(keyword, name, expr.id),
         }
     }
     
 }
+
+/// Calling a generator function only creates the generator. Its body runs when
+/// the first value is asked for.
+#[inline(never)]
+fn generator(function: Rc<RefCell<Function>>, arguments: Vec<Object>) -> Object {
+    let name = function.borrow().name.clone();
+    let body = Body::new(move |interpreter, _, line| interpreter.execute_function(function, arguments, line));
+    Object::Generator(Rc::new(RefCell::new(Generator::new(name, body))))
+}
+
+/// Name of the method an instance has to define to overload a binary operator.
+fn operator_method(operator: &TokenType) -> Option<&'static str> {
+    match operator {
+        TokenType::Plus => Some("__add"),
+        TokenType::Minus => Some("__sub"),
+        TokenType::Star => Some("__mul"),
+        TokenType::Slash => Some("__div"),
+        TokenType::Percent => Some("__mod"),
+        TokenType::StarStar => Some("__pow"),
+        TokenType::TildeSlash => Some("__floordiv"),
+        TokenType::Less => Some("__lt"),
+        TokenType::LessEqual => Some("__le"),
+        TokenType::Greater => Some("__gt"),
+        TokenType::GreaterEqual => Some("__ge"),
+        _ => None
+    }
+}
+
+/// The method that implements a comparison with its operands swapped.
+fn reflected_method(operator: &TokenType) -> Option<&'static str> {
+    match operator {
+        TokenType::Less => Some("__gt"),
+        TokenType::LessEqual => Some("__ge"),
+        TokenType::Greater => Some("__lt"),
+        TokenType::GreaterEqual => Some("__le"),
+        _ => None
+    }
+}
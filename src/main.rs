@@ -12,27 +12,68 @@ mod environment;
 mod resolver;
 mod interpreter;
 mod native;
+mod module;
+mod map;
+mod generator;
+mod fiber;
+mod enumeration;
+mod range;
+mod checker;
+mod strict;
+mod flow;
+mod optimizer;
+mod printer;
 
 use std::{collections::HashMap, env, fs::read_to_string, io::{self, Write}, path::PathBuf, process::exit};
+use checker::check;
+use error::report_exception;
+use flow::check_flow;
 use interpreter::Interpreter;
+use optimizer::optimize;
 use parser::parse;
+use printer::print_ast;
 use resolver::resolve;
 use scanner::scan_tokens;
+use strict::analyze;
+
+/// Flags given before the script.
+#[derive(Default)]
+struct Options {
+    // Analyze the script in strict mode before running it.
+    strict: bool,
+    // Print the optimized AST instead of running the script.
+    dump_ast: bool,
+}
 
 fn main() {
-    let args: Vec<String> = env::args().collect();
-    if args.len() > 2 {
-        println!("Usage: lox-rs <script>");
-        exit(64);
-    } else if args.len() == 2 {
-        run_file(args[1].clone());
-    } else {
-        run_prompt();
+    let mut args: Vec<String> = env::args().skip(1).collect();
+    let mut options = Options::default();
+    while let Some(flag) = args.first().filter(|arg| arg.starts_with("--")) {
+        match flag.as_str() {
+            "--strict" => options.strict = true,
+            "--dump-ast" => options.dump_ast = true,
+            _ => usage()
+        }
+        args.remove(0);
+    }
+    let flagged = options.strict || options.dump_ast;
+
+    match args.as_slice() {
+        [command, script] if command == "check" && !flagged => check_file(script.clone()),
+        [script] => run_file(script.clone(), options),
+        [] if !flagged => run_prompt(),
+        _ => usage()
     }
 }
 
+fn usage() -> ! {
+    println!("Usage: lox-rs [--strict] [--dump-ast] [script] | lox-rs check <script>");
+    exit(64);
+}
+
 fn run_prompt() {
     let mut interpreter = Interpreter::new(HashMap::new());
+    interpreter.set_search_path(search_path());
     loop {
         let mut input = String::new();
         print!("> ");
@@ -43,7 +84,10 @@ fn run_prompt() {
                 let statements = parse(tokens);
                 let locals = resolve(&statements);
                 interpreter.extend_locals(locals);
-                interpreter.interpret(&statements);
+                let statements = optimize(statements);
+                if let Err(exception) = interpreter.interpret(&statements) {
+                    report_exception(&exception);
+                }
             }
             Err(error) => {
                 println!("Error reading input: {}", error);
@@ -53,16 +97,49 @@ fn run_prompt() {
     }
 }
 
-fn run_file(script_path: String) {
+fn run_file(script_path: String, options: Options) {
     let script_path_buf = PathBuf::from(script_path);
-    let source = read_to_string(script_path_buf).expect("Could not read script.");
-    run(source);
+    let source = read_to_string(&script_path_buf).expect("Could not read script.");
+    run(source, script_path_buf, options);
+}
+
+/// Type checks a script without running it.
+fn check_file(script_path: String) {
+    let source = read_to_string(script_path).expect("Could not read script.");
+    let statements = parse(scan_tokens(source));
+    resolve(&statements);
+    check_flow(&statements);
+    if check(&statements) > 0 {
+        exit(65);
+    }
+}
+
+/// Additional module directories, taken from the `LOX_PATH` environment variable.
+fn search_path() -> Vec<PathBuf> {
+    match env::var_os("LOX_PATH") {
+        Some(paths) => env::split_paths(&paths).collect(),
+        None => Vec::new()
+    }
 }
 
-fn run(source: String) {  
+fn run(source: String, script_path: PathBuf, options: Options) {  
     let tokens = scan_tokens(source);
     let statements = parse(tokens);
     let locals = resolve(&statements);
+    check_flow(&statements);
+    if options.strict && analyze(&statements, &locals, Some(&script_path), &search_path()) > 0 {
+        exit(65);
+    }
+    let statements = optimize(statements);
+    if options.dump_ast {
+        println!("{}", print_ast(&statements));
+        return;
+    }
     let mut interpreter = Interpreter::new(locals);
-    interpreter.interpret(&statements);
+    interpreter.set_script_path(script_path);
+    interpreter.set_search_path(search_path());
+    if let Err(exception) = interpreter.interpret(&statements) {
+        report_exception(&exception);
+        exit(70);
+    }
 }
\ No newline at end of file
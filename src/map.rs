@@ -0,0 +1,67 @@
+use std::{collections::HashMap, fmt::Display};
+
+use crate::object::Object;
+
+/// Insertion ordered map keyed by Lox values. Computing a key's hash and
+/// comparing keys may run Lox code (`hash()` and `equals()`), so the
+/// interpreter does both and only hands the results to the map.
+#[derive(Default)]
+pub struct Map {
+    entries: Vec<(Object, Object, u64)>,
+    buckets: HashMap<u64, Vec<usize>>,
+}
+
+impl Map {
+
+    pub fn new() -> Self {
+        Map::default()
+    }
+
+    /// Keys with the given hash, together with their entry index.
+    pub fn candidates(&self, hash: u64) -> Vec<(usize, Object)> {
+        match self.buckets.get(&hash) {
+            Some(indices) => indices.iter().map(|index| (*index, self.entries[*index].0.clone())).collect(),
+            None => Vec::new()
+        }
+    }
+
+    pub fn value(&self, index: usize) -> Object {
+        self.entries[index].1.clone()
+    }
+
+    pub fn replace(&mut self, index: usize, value: Object) {
+        self.entries[index].1 = value;
+    }
+
+    pub fn insert(&mut self, hash: u64, key: Object, value: Object) {
+        self.buckets.entry(hash).or_default().push(self.entries.len());
+        self.entries.push((key, value, hash));
+    }
+
+    pub fn remove(&mut self, index: usize) -> Object {
+        let (_, value, _) = self.entries.remove(index);
+        self.buckets.clear();
+        for (index, (_, _, hash)) in self.entries.iter().enumerate() {
+            self.buckets.entry(*hash).or_default().push(index);
+        }
+        value
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn entries(&self) -> Vec<(Object, Object)> {
+        self.entries.iter().map(|(key, value, _)| (key.clone(), value.clone())).collect()
+    }
+
+}
+
+impl Display for Map {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let entries: Vec<String> = self.entries.iter()
+            .map(|(key, value, _)| format!("{key}: {value}"))
+            .collect();
+        write!(f, "{{{}}}", entries.join(", "))
+    }
+}
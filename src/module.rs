@@ -0,0 +1,55 @@
+use std::{collections::HashMap, path::{Path, PathBuf}};
+
+use crate::{object::Object, stmt::Stmt};
+
+/// Resolves an import path relative to the importing file first and then
+/// against each directory of the search path.
+pub fn resolve_path(path: &str, importer: Option<&PathBuf>, search_path: &[PathBuf]) -> Option<PathBuf> {
+    let base = match importer.and_then(|importer| importer.parent()) {
+        Some(directory) => directory.to_path_buf(),
+        None => PathBuf::from(".")
+    };
+
+    let candidates = std::iter::once(base).chain(search_path.iter().cloned());
+    for directory in candidates {
+        let candidate = directory.join(Path::new(path));
+        if candidate.is_file() {
+            return candidate.canonicalize().ok();
+        }
+    }
+
+    None
+}
+
+/// Exported values by name, together with whether they are constants.
+pub type Exports = HashMap<String, (Object, bool)>;
+
+/// Collects the values of all top-level declarations marked with `export`.
+pub fn exports(statements: &Vec<Stmt>, lookup: impl Fn(&String) -> Option<Object>) -> Exports {
+    let mut exports = HashMap::new();
+    for statement in statements {
+        if let Some((name, constant)) = exported(statement) {
+            if let Some(value) = lookup(name) {
+                exports.insert(name.clone(), (value, constant));
+            }
+        }
+    }
+    exports
+}
+
+/// The name of a top-level statement marked with `export` and whether it is a constant.
+pub fn exported(statement: &Stmt) -> Option<(&String, bool)> {
+    let declaration = match statement {
+        Stmt::Export(declaration) => declaration.as_ref(),
+        _ => return None
+    };
+    match declaration {
+        Stmt::Var(name, _, _) => Some((&name.lexeme, false)),
+        Stmt::Const(name, _, _) => Some((&name.lexeme, true)),
+        Stmt::Function(name, _, _, _, _) => Some((&name.lexeme, false)),
+        Stmt::Class(name, _, _, _, _, _, _, _) => Some((&name.lexeme, false)),
+        Stmt::Trait(name, _, _, _) => Some((&name.lexeme, false)),
+        Stmt::Enum(name, _) => Some((&name.lexeme, false)),
+        _ => None
+    }
+}
@@ -1,30 +1,82 @@
-use std::{fmt::Display, fs::read_to_string, io::{self, Write}, path::PathBuf, time::{SystemTime, UNIX_EPOCH}};
+use std::{cell::RefCell, fmt::Display, fs::read_to_string, io::{self, Write}, path::PathBuf, rc::Rc, time::{SystemTime, UNIX_EPOCH}};
 
-use crate::object::Object;
+use crate::{fiber::Fiber, map::Map, object::Object};
 
 #[derive(Clone)]
 pub enum NativeFunction {
     Clock,
     Input,
-    ReadFile
+    ReadFile,
+    Map,
+    Fiber,
+    Methods,
+    Fields
 }
 
 impl NativeFunction {
 
-    pub fn call(&self, args: Vec<Object>) -> Object {
+    /// All natives, each defined as a global under its name.
+    pub const ALL: [NativeFunction; 7] = [
+        NativeFunction::Clock,
+        NativeFunction::Input,
+        NativeFunction::ReadFile,
+        NativeFunction::Map,
+        NativeFunction::Fiber,
+        NativeFunction::Methods,
+        NativeFunction::Fields
+    ];
+
+    /// Number of arguments the native expects.
+    pub fn arity(&self) -> usize {
+        match self {
+            NativeFunction::Clock | NativeFunction::Input | NativeFunction::Map => 0,
+            NativeFunction::ReadFile | NativeFunction::Fiber | NativeFunction::Methods | NativeFunction::Fields => 1
+        }
+    }
+
+    pub fn call(&self, args: Vec<Object>) -> Result<Object, String> {
         match self {
-            NativeFunction::Clock => self.clock(),
-            NativeFunction::Input => self.input(),
+            NativeFunction::Clock => Ok(self.clock()),
+            NativeFunction::Input => Ok(self.input()),
+            NativeFunction::Map => Ok(Object::Map(Rc::new(RefCell::new(Map::new())))),
+            NativeFunction::Fiber => {
+                match args.first() {
+                    Some(Object::Function(function)) => {
+                        if function.borrow().min_arity() > 1 {
+                            return Err("Native function [Fiber]: Function must take at most one parameter.".to_string());
+                        }
+                        Ok(Object::Fiber(Rc::new(RefCell::new(Fiber::new(function.clone())))))
+                    },
+                    Some(_) => Err("Native function [Fiber]: Expecting function argument.".to_string()),
+                    None => Err("Native function [Fiber]: Expecting argument [function].".to_string())
+                }
+            },
+            NativeFunction::Methods => {
+                let names = match args.first() {
+                    Some(Object::Class(class)) => class.borrow().method_names(),
+                    Some(Object::Instance(instance)) => instance.borrow().class.borrow().method_names(),
+                    Some(_) => return Err("Native function [methods]: Expecting class or instance argument.".to_string()),
+                    None => return Err("Native function [methods]: Expecting argument [class].".to_string())
+                };
+                Ok(self.names(names))
+            },
+            NativeFunction::Fields => {
+                match args.first() {
+                    Some(Object::Instance(instance)) => Ok(self.names(instance.borrow().field_names())),
+                    Some(_) => Err("Native function [fields]: Expecting instance argument.".to_string()),
+                    None => Err("Native function [fields]: Expecting argument [instance].".to_string())
+                }
+            },
             NativeFunction::ReadFile => {
                 match args.first() {
                     Some(obj) => {
                         if let Object::String(path) = obj {
                             self.read_file(path.clone())
                         } else {
-                            panic!("Native function [read_file]: Expecting string as path argument.")
+                            Err("Native function [readFile]: Expecting string as path argument.".to_string())
                         }
                     },
-                    None => panic!("Native function [read_file]: Expecting argument [path].")
+                    None => Err("Native function [readFile]: Expecting argument [path].".to_string())
                 }
             },
         }
@@ -44,12 +96,18 @@ impl NativeFunction {
         }
     }
 
-    fn read_file(&self, path: String) -> Object {
+    /// Lists member names, leaving out private ones.
+    fn names(&self, names: Vec<String>) -> Object {
+        let names = names.into_iter().filter(|name| !name.contains('#')).map(Object::String).collect();
+        Object::List(Rc::new(RefCell::new(names)))
+    }
+
+    fn read_file(&self, path: String) -> Result<Object, String> {
         let path_buf = PathBuf::from(path.trim());
         
         match read_to_string(path_buf) {
-            Ok(data) => Object::String(data),
-            Err(e) => panic!("{}", e)
+            Ok(data) => Ok(Object::String(data)),
+            Err(e) => Err(format!("Could not read file '{}': {}", path.trim(), e))
         }
     }
 }
@@ -57,9 +115,13 @@ impl NativeFunction {
 impl Display for NativeFunction {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            NativeFunction::Clock => write!(f, "{}", "clock"),
-            NativeFunction::Input => write!(f, "{}", "input"),
-            NativeFunction::ReadFile => write!(f, "{}", "readFile"),
+            NativeFunction::Clock => write!(f, "clock"),
+            NativeFunction::Input => write!(f, "input"),
+            NativeFunction::ReadFile => write!(f, "readFile"),
+            NativeFunction::Map => write!(f, "Map"),
+            NativeFunction::Fiber => write!(f, "Fiber"),
+            NativeFunction::Methods => write!(f, "methods"),
+            NativeFunction::Fields => write!(f, "fields"),
         }
     }
 }
\ No newline at end of file
@@ -1,5 +1,5 @@
 use std::{rc::Rc, cell::RefCell, fmt::Display};
-use crate::{class::Class, function::Function, instance::Instance, native::NativeFunction};
+use crate::{class::{Class, Trait}, enumeration::{Enum, Member}, fiber::Fiber, function::Function, generator::Generator, instance::Instance, map::Map, native::NativeFunction, range::Range};
 
 #[derive(Clone)]
 pub enum Object {
@@ -10,11 +10,41 @@ pub enum Object {
     NativeFunction(NativeFunction),
     Class(Rc<RefCell<Class>>),
     Instance(Rc<RefCell<Instance>>),
+    List(Rc<RefCell<Vec<Object>>>),
+    Map(Rc<RefCell<Map>>),
+    NativeMethod(Box<Object>, String),
+    Generator(Rc<RefCell<Generator>>),
+    Fiber(Rc<RefCell<Fiber>>),
+    Trait(Rc<Trait>),
+    Enum(Rc<Enum>),
+    EnumMember(Rc<Member>),
+    Range(Range),
     Nil
 }
 
 impl Object {
 
+    /// Name of the value's type as returned by `typeof`.
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            Object::Number(_) => "number",
+            Object::String(_) => "string",
+            Object::Boolean(_) => "bool",
+            Object::Function(_) | Object::NativeFunction(_) | Object::NativeMethod(_, _) => "function",
+            Object::Class(_) => "class",
+            Object::Instance(_) => "instance",
+            Object::List(_) => "list",
+            Object::Map(_) => "map",
+            Object::Generator(_) => "generator",
+            Object::Fiber(_) => "fiber",
+            Object::Trait(_) => "trait",
+            Object::Enum(_) => "enum",
+            Object::EnumMember(_) => "enum member",
+            Object::Range(_) => "range",
+            Object::Nil => "nil"
+        }
+    }
+
     pub fn is_truthy(&self) -> bool {
         match self {
             Object::Boolean(false) => false,
@@ -31,12 +61,20 @@ impl PartialEq for Object {
             (Self::Number(l), Self::Number(r)) => l == r,
             (Self::String(l), Self::String(r)) => l == r,
             (Self::Boolean(l), Self::Boolean(r)) => l == r,
-            (Self::Function(l), Self::Function(r)) => {
-                l.borrow().name == r.borrow().name
-            },
-            (Self::Class(l), Self::Class(r)) => {
-                l.borrow().name == r.borrow().name
-            },
+            (Self::Function(l), Self::Function(r)) => Rc::ptr_eq(l, r) || l.borrow().same_binding(&r.borrow()),
+            (Self::NativeFunction(l), Self::NativeFunction(r)) => l.to_string() == r.to_string(),
+            (Self::Class(l), Self::Class(r)) => Rc::ptr_eq(l, r),
+            (Self::Instance(l), Self::Instance(r)) => Rc::ptr_eq(l, r),
+            (Self::List(l), Self::List(r)) => Rc::ptr_eq(l, r),
+            (Self::Map(l), Self::Map(r)) => Rc::ptr_eq(l, r),
+            (Self::NativeMethod(l, l_name), Self::NativeMethod(r, r_name)) => l == r && l_name == r_name,
+            (Self::Generator(l), Self::Generator(r)) => Rc::ptr_eq(l, r),
+            (Self::Fiber(l), Self::Fiber(r)) => Rc::ptr_eq(l, r),
+            (Self::Trait(l), Self::Trait(r)) => Rc::ptr_eq(l, r),
+            (Self::Enum(l), Self::Enum(r)) => Rc::ptr_eq(l, r),
+            (Self::EnumMember(l), Self::EnumMember(r)) => Rc::ptr_eq(l, r),
+            (Self::Range(l), Self::Range(r)) => l == r,
+            (Self::Nil, Self::Nil) => true,
             _ => false
         }
     }
@@ -53,6 +91,18 @@ impl Display for Object {
             Object::Instance(instance) => write!(f, "{}", instance.borrow()),
             Object::Nil => write!(f, "nil"),
             Object::NativeFunction(name) => write!(f, "{name}"),
+            Object::List(list) => {
+                let elements: Vec<String> = list.borrow().iter().map(|element| element.to_string()).collect();
+                write!(f, "[{}]", elements.join(", "))
+            },
+            Object::Map(map) => write!(f, "{}", map.borrow()),
+            Object::NativeMethod(_, name) => write!(f, "{name} native method"),
+            Object::Generator(generator) => write!(f, "{}", generator.borrow()),
+            Object::Fiber(fiber) => write!(f, "{}", fiber.borrow()),
+            Object::Trait(used) => write!(f, "{used}"),
+            Object::Enum(enumeration) => write!(f, "{enumeration}"),
+            Object::EnumMember(member) => write!(f, "{member}"),
+            Object::Range(range) => write!(f, "{range}"),
         }
     }
 }
\ No newline at end of file
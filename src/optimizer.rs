@@ -0,0 +1,286 @@
+use std::rc::Rc;
+
+use crate::{expr::{Expr, ExprType}, object::Object, stmt::{Case, Parameter, Pattern, Stmt}, token::{Token, TokenType}};
+
+/// Simplifies a resolved program: folds operations on literals into their
+/// result, drops `if` branches and `while` loops whose condition is a literal
+/// that rules them out, and removes expression statements without effects.
+/// Expressions that remain keep their ids, so the resolved locals stay valid,
+/// and nothing that could fail at runtime is folded, so errors are still
+/// reported when and where they happen.
+pub fn optimize(statements: Vec<Stmt>) -> Vec<Stmt> {
+    statements.into_iter().filter_map(statement).collect()
+}
+
+/// Optimizes a statement that must stay a statement, such as a loop body.
+fn required(stmt: Stmt) -> Stmt {
+    statement(stmt).unwrap_or(Stmt::Block(Vec::new()))
+}
+
+fn statement(stmt: Stmt) -> Option<Stmt> {
+    let stmt = match stmt {
+        Stmt::Assign(mut targets, equals, mut values) => {
+            targets.iter_mut().chain(values.iter_mut()).for_each(expression);
+            Stmt::Assign(targets, equals, values)
+        },
+        Stmt::Block(statements) => Stmt::Block(optimize(statements)),
+        Stmt::Class(name, mut superclass, mut traits, methods, getters, setters, fields, abstracts) => {
+            superclass.iter_mut().chain(traits.iter_mut()).for_each(expression);
+            Stmt::Class(name, superclass, traits, optimize(methods), optimize(getters), optimize(setters), optimize(fields), optimize(abstracts))
+        },
+        Stmt::Const(name, mut initializer, annotation) => {
+            expression(&mut initializer);
+            Stmt::Const(name, initializer, annotation)
+        },
+        Stmt::Export(declaration) => Stmt::Export(Box::new(required(*declaration))),
+        Stmt::Expression(mut expr) => {
+            expression(&mut expr);
+            if !has_effects(&expr) {
+                return None;
+            }
+            Stmt::Expression(expr)
+        },
+        Stmt::ForIn(name, mut iterable, body) => {
+            expression(&mut iterable);
+            Stmt::ForIn(name, iterable, Box::new(required(*body)))
+        },
+        Stmt::Function(name, parameters, body, generator, returns) => {
+            let mut parameters: Vec<Parameter> = parameters.as_ref().clone();
+            parameters.iter_mut().filter_map(|parameter| parameter.default.as_mut()).for_each(expression);
+            let body = optimize(body.as_ref().clone());
+            Stmt::Function(name, Rc::new(parameters), Rc::new(body), generator, returns)
+        },
+        Stmt::If(mut condition, then_branch, else_branch) => {
+            expression(&mut condition);
+            if let ExprType::Literal(value) = &condition.expr_type {
+                return match (value.is_truthy(), *else_branch) {
+                    (true, _) => statement(*then_branch),
+                    (false, Some(else_branch)) => statement(else_branch),
+                    (false, None) => None
+                };
+            }
+            Stmt::If(condition, Box::new(required(*then_branch)), Box::new(else_branch.map(required)))
+        },
+        Stmt::Match(keyword, mut subject, cases) => {
+            expression(&mut subject);
+            let cases = cases.into_iter().map(|mut case| {
+                case.patterns.iter_mut().for_each(pattern);
+                case.guard.iter_mut().for_each(expression);
+                Case { body: required(case.body), ..case }
+            }).collect();
+            Stmt::Match(keyword, subject, cases)
+        },
+        Stmt::Print(keyword, mut expr) => {
+            expression(&mut expr);
+            Stmt::Print(keyword, expr)
+        },
+        Stmt::Return(keyword, mut value) => {
+            value.iter_mut().for_each(expression);
+            Stmt::Return(keyword, value)
+        },
+        Stmt::Throw(keyword, mut expr) => {
+            expression(&mut expr);
+            Stmt::Throw(keyword, expr)
+        },
+        Stmt::Trait(name, methods, getters, setters) => Stmt::Trait(name, optimize(methods), optimize(getters), optimize(setters)),
+        Stmt::Try(body, catch, finally) => {
+            let catch = catch.map(|(name, catch)| (name, optimize(catch)));
+            Stmt::Try(optimize(body), Box::new(catch), Box::new(finally.map(optimize)))
+        },
+        Stmt::Var(name, mut initializer, annotation) => {
+            expression(&mut initializer);
+            Stmt::Var(name, initializer, annotation)
+        },
+        Stmt::VarDestructure(destructure, mut initializer) => {
+            expression(&mut initializer);
+            Stmt::VarDestructure(destructure, initializer)
+        },
+        Stmt::While(keyword, mut condition, body) => {
+            expression(&mut condition);
+            if let ExprType::Literal(value) = &condition.expr_type {
+                if !value.is_truthy() {
+                    return None;
+                }
+            }
+            Stmt::While(keyword, condition, Box::new(required(*body)))
+        },
+        Stmt::Yield(keyword, mut expr) => {
+            expression(&mut expr);
+            Stmt::Yield(keyword, expr)
+        },
+        stmt @ (Stmt::Enum(_, _) | Stmt::Import(_, _)) => stmt
+    };
+    Some(stmt)
+}
+
+fn pattern(pattern: &mut Pattern) {
+    match pattern {
+        Pattern::Value(value) | Pattern::Class(value, _) => expression(value),
+        Pattern::List(elements, _) => elements.iter_mut().for_each(self::pattern),
+        Pattern::Wildcard | Pattern::Binding(_) => {}
+    }
+}
+
+/// Whether evaluating an expression can do anything but produce a value.
+fn has_effects(expr: &Expr) -> bool {
+    match &expr.expr_type {
+        ExprType::Literal(_) | ExprType::This(_) => false,
+        ExprType::Grouping(expr) => has_effects(expr),
+        ExprType::List(elements) => elements.iter().any(has_effects),
+        ExprType::Logical(left, _, right) => has_effects(left) || has_effects(right),
+        ExprType::Conditional(condition, then_branch, else_branch) => has_effects(condition)
+            || has_effects(then_branch) || has_effects(else_branch),
+        _ => true
+    }
+}
+
+fn literal(expr: &Expr) -> Option<&Object> {
+    match &expr.expr_type {
+        ExprType::Literal(value) => Some(value),
+        _ => None
+    }
+}
+
+/// Optimizes the operands of an expression and replaces it with a literal if
+/// its value is known, or with the operand a literal condition selects.
+fn expression(expr: &mut Expr) {
+    let id = expr.id;
+    let literal_of = |expr_type: Option<ExprType>| expr_type.map(|expr_type| Expr { id, expr_type });
+    let folded = match &mut expr.expr_type {
+        ExprType::Assign(_, value) | ExprType::CompoundAssign(_, _, value, _) => {
+            expression(value);
+            None
+        },
+        ExprType::Binary(left, op, right) => {
+            expression(left);
+            expression(right);
+            match (literal(left), literal(right)) {
+                (Some(left), Some(right)) => literal_of(binary(left, op, right)),
+                _ => None
+            }
+        },
+        ExprType::Call(callee, _, arguments) => {
+            expression(callee);
+            arguments.iter_mut().for_each(expression);
+            None
+        },
+        ExprType::CompoundSet(object, _, _, value, _) | ExprType::Set(object, _, value) => {
+            expression(object);
+            expression(value);
+            None
+        },
+        ExprType::Conditional(condition, then_branch, else_branch) => {
+            expression(condition);
+            expression(then_branch);
+            expression(else_branch);
+            match literal(condition) {
+                Some(condition) if condition.is_truthy() => Some(then_branch.as_ref().clone()),
+                Some(_) => Some(else_branch.as_ref().clone()),
+                None => None
+            }
+        },
+        ExprType::Get(object, _) | ExprType::OptionalGet(object, _) | ExprType::OptionalChain(object) => {
+            expression(object);
+            None
+        },
+        ExprType::Grouping(inner) => {
+            expression(inner);
+            literal(inner).map(|_| inner.as_ref().clone())
+        },
+        ExprType::Index(object, _, index) => {
+            expression(object);
+            expression(index);
+            None
+        },
+        ExprType::List(elements) => {
+            elements.iter_mut().for_each(expression);
+            None
+        },
+        ExprType::Logical(left, op, right) => {
+            expression(left);
+            expression(right);
+            // The left operand decides whether the right one is evaluated.
+            match literal(left) {
+                Some(value) => {
+                    let short_circuits = match op.token_type {
+                        TokenType::Or => value.is_truthy(),
+                        TokenType::QuestionQuestion => !matches!(value, Object::Nil),
+                        _ => !value.is_truthy()
+                    };
+                    Some(if short_circuits { left.as_ref().clone() } else { right.as_ref().clone() })
+                },
+                None => None
+            }
+        },
+        ExprType::Range(start, _, end, step) => {
+            expression(start);
+            expression(end);
+            step.iter_mut().for_each(|step| expression(step));
+            None
+        },
+        ExprType::CompoundSetIndex(object, _, index, _, value, _) | ExprType::SetIndex(object, _, index, value) => {
+            expression(object);
+            expression(index);
+            expression(value);
+            None
+        },
+        ExprType::Unary(op, right) => {
+            expression(right);
+            literal_of(literal(right).and_then(|right| unary(op, right)))
+        },
+        ExprType::Literal(_) | ExprType::Super(_, _) | ExprType::This(_) | ExprType::Variable(_) => None
+    };
+
+    if let Some(folded) = folded {
+        *expr = folded;
+    }
+}
+
+/// The value of an operator applied to literals, if it can be computed
+/// without an error. Instances never appear as literals, so no overloaded
+/// operator can be involved.
+fn binary(left: &Object, op: &Token, right: &Object) -> Option<ExprType> {
+    let value = match (op.token_type.clone(), left, right) {
+        (TokenType::EqualEqual, _, _) => Object::Boolean(left == right),
+        (TokenType::BangEqual, _, _) => Object::Boolean(left != right),
+        (TokenType::Plus, Object::String(_), _) | (TokenType::Plus, _, Object::String(_)) => {
+            Object::String(format!("{}{}", left, right))
+        },
+        (token_type, Object::Number(l), Object::Number(r)) => {
+            let (l, r) = (*l, *r);
+            match token_type {
+                TokenType::Plus => Object::Number(l + r),
+                TokenType::Minus => Object::Number(l - r),
+                TokenType::Star => Object::Number(l * r),
+                TokenType::Slash => Object::Number(l / r),
+                TokenType::Percent => Object::Number(l - r * (l / r).floor()),
+                TokenType::TildeSlash => Object::Number((l / r).floor()),
+                TokenType::StarStar => Object::Number(l.powf(r)),
+                TokenType::Greater => Object::Boolean(l > r),
+                TokenType::GreaterEqual => Object::Boolean(l >= r),
+                TokenType::Less => Object::Boolean(l < r),
+                TokenType::LessEqual => Object::Boolean(l <= r),
+                _ if l.fract() != 0.0 || r.fract() != 0.0 => return None,
+                TokenType::Ampersand => Object::Number(((l as i64) & (r as i64)) as f64),
+                TokenType::Pipe => Object::Number(((l as i64) | (r as i64)) as f64),
+                TokenType::Caret => Object::Number(((l as i64) ^ (r as i64)) as f64),
+                TokenType::LessLess if (0.0..64.0).contains(&r) => Object::Number(((l as i64) << (r as i64)) as f64),
+                TokenType::GreaterGreater if (0.0..64.0).contains(&r) => Object::Number(((l as i64) >> (r as i64)) as f64),
+                _ => return None
+            }
+        },
+        _ => return None
+    };
+    Some(ExprType::Literal(value))
+}
+
+fn unary(op: &Token, right: &Object) -> Option<ExprType> {
+    let value = match (op.token_type.clone(), right) {
+        (TokenType::Bang, _) => Object::Boolean(!right.is_truthy()),
+        (TokenType::TypeOf, _) => Object::String(String::from(right.type_name())),
+        (TokenType::Minus, Object::Number(n)) => Object::Number(-n),
+        (TokenType::Tilde, Object::Number(n)) if n.fract() == 0.0 => Object::Number(!(*n as i64) as f64),
+        _ => return None
+    };
+    Some(ExprType::Literal(value))
+}
@@ -1,5 +1,5 @@
-use std::{collections::VecDeque, rc::Rc};
-use crate::{token::{Token, TokenType}, expr::{Expr, ExprType}, error::{report, error}, object::Object, stmt::Stmt};
+use std::{collections::VecDeque, rc::Rc, sync::atomic::{AtomicUsize, Ordering}};
+use crate::{token::{Token, TokenType}, expr::{Expr, ExprType}, error::{report, error}, object::Object, stmt::{Case, Destructure, Parameter, Pattern, Stmt, TypeAnnotation}};
 
 pub fn parse(tokens: Vec<Token>) -> Vec<Stmt> {
     let mut parser = Parser::new(tokens);
@@ -12,15 +12,28 @@ pub fn parse(tokens: Vec<Token>) -> Vec<Stmt> {
     statements
 }
 
+/// Id of the next class or trait declaration. It is shared by all parsers, so
+/// declarations in different modules never get the same one.
+static NEXT_DECLARATION: AtomicUsize = AtomicUsize::new(0);
+
+/// Methods, getters, setters, fields and abstract methods of a class body.
+type ClassBody = (Vec<Stmt>, Vec<Stmt>, Vec<Stmt>, Vec<Stmt>, Vec<Stmt>);
+
 struct Parser {
-    tokens: VecDeque<Token>
+    tokens: VecDeque<Token>,
+    // One entry per function being parsed, set once its body contains a `yield`.
+    generators: Vec<bool>,
+    // Unique ids of the class and trait declarations being parsed, innermost last.
+    classes: Vec<usize>,
 }
 
 impl Parser {
 
     fn new(tokens: Vec<Token>) -> Self {
         Parser {
-            tokens: tokens.into()
+            tokens: tokens.into(),
+            generators: Vec::new(),
+            classes: Vec::new()
         }
     }
 
@@ -30,6 +43,14 @@ impl Parser {
                 self.advance();
                 self.class_declaration()
             },
+            TokenType::Trait => {
+                self.advance();
+                self.trait_declaration()
+            },
+            TokenType::Enum => {
+                self.advance();
+                self.enum_declaration()
+            },
             TokenType::Fun => {
                 self.advance();
                 self.function("function")
@@ -38,11 +59,55 @@ impl Parser {
                 self.consume(&TokenType::Var);
                 self.var_declaration()
             },
+            TokenType::Const => {
+                self.advance();
+                self.const_declaration()
+            },
+            TokenType::Import => {
+                self.advance();
+                self.import_declaration()
+            },
+            TokenType::Export => {
+                self.advance();
+                self.export_declaration()
+            },
             _ => self.statement()
         }
         // TODO: synchronize on error
     }
 
+    fn import_declaration(&mut self) -> Stmt {
+        let names = if self.consume(&TokenType::LeftBrace).is_some() {
+            let mut names = Vec::new();
+            loop {
+                names.push(self.consume(&TokenType::Identifier).expect("Expect imported name."));
+                if self.consume(&TokenType::Comma).is_none() {
+                    break;
+                }
+            }
+            self.consume(&TokenType::RightBrace).expect("Expect '}' after imported names.");
+            self.consume(&TokenType::From).expect("Expect 'from' after imported names.");
+            Some(names)
+        } else {
+            None
+        };
+
+        let path = self.consume(&TokenType::String).expect("Expect module path.");
+        self.consume(&TokenType::Semicolon).expect("Expect ';' after import.");
+        Stmt::Import(Box::from(path), names)
+    }
+
+    fn export_declaration(&mut self) -> Stmt {
+        let declaration = match self.peek().token_type {
+            TokenType::Class | TokenType::Trait | TokenType::Enum | TokenType::Fun | TokenType::Var | TokenType::Const => self.declaration(),
+            _ => {
+                report(self.peek().line, format!("at {}", self.peek().lexeme), String::from("Expect declaration after 'export'."));
+                panic!("Expect declaration after 'export'.");
+            }
+        };
+        Stmt::Export(Box::from(declaration))
+    }
+
     fn class_declaration(&mut self) -> Stmt {
         let name = self.consume(&TokenType::Identifier).expect("Expect class name.");
 
@@ -52,30 +117,162 @@ impl Parser {
             None
         };
 
+        let mut traits = vec![];
+        if self.consume(&TokenType::With).is_some() {
+            loop {
+                let name = self.consume(&TokenType::Identifier).expect("Expect trait name.");
+                traits.push(Expr::new(ExprType::Variable(Box::new(name))));
+                if self.consume(&TokenType::Comma).is_none() {
+                    break;
+                }
+            }
+        }
+
         self.consume(&TokenType::LeftBrace).expect("Expect '{' before class body.");
+        self.classes.push(NEXT_DECLARATION.fetch_add(1, Ordering::Relaxed));
+        let (methods, getters, setters, fields, abstracts) = self.class_body();
+        self.classes.pop();
+        self.consume(&TokenType::RightBrace).expect("Expect '}' after class body");
+        Stmt::Class(Box::from(name), Box::from(superclass), traits, methods, getters, setters, fields, abstracts)
 
+    }
+
+    fn trait_declaration(&mut self) -> Stmt {
+        let name = self.consume(&TokenType::Identifier).expect("Expect trait name.");
+        self.consume(&TokenType::LeftBrace).expect("Expect '{' before trait body.");
+        self.classes.push(NEXT_DECLARATION.fetch_add(1, Ordering::Relaxed));
+        let (methods, getters, setters, fields, abstracts) = self.class_body();
+        self.classes.pop();
+        if !fields.is_empty() || !abstracts.is_empty() {
+            report(name.line, format!("at {}", name.lexeme), String::from("A trait can't declare fields or abstract methods."));
+            panic!("A trait can't declare fields or abstract methods.");
+        }
+        self.consume(&TokenType::RightBrace).expect("Expect '}' after trait body.");
+        Stmt::Trait(Box::from(name), methods, getters, setters)
+    }
+
+    fn enum_declaration(&mut self) -> Stmt {
+        let name = self.consume(&TokenType::Identifier).expect("Expect enum name.");
+        self.consume(&TokenType::LeftBrace).expect("Expect '{' before enum body.");
+        let mut members: Vec<Token> = Vec::new();
+        while !self.check(&TokenType::RightBrace) {
+            let member = self.consume(&TokenType::Identifier).expect("Expect enum member name.");
+            if members.iter().any(|other| other.lexeme == member.lexeme) {
+                report(member.line, format!("at {}", member.lexeme), String::from("Duplicate enum member."));
+                panic!("Duplicate enum member.");
+            }
+            members.push(member);
+            if self.consume(&TokenType::Comma).is_none() {
+                break;
+            }
+        }
+        self.consume(&TokenType::RightBrace).expect("Expect '}' after enum body.");
+        Stmt::Enum(Box::from(name), members)
+    }
+
+    /// Parses the methods, getters, setters, declared fields and abstract
+    /// methods of a class or trait body.
+    fn class_body(&mut self) -> ClassBody {
         let mut methods = vec![];
+        let mut getters = vec![];
+        let mut setters = vec![];
+        let mut fields = vec![];
+        let mut abstracts = vec![];
         while !self.check(&TokenType::RightBrace) && !self.is_at_end() {
-            methods.push(self.function("method"));
+            let field = self.consume(&TokenType::Var).is_some();
+            let abstract_method = !field && self.consume(&TokenType::Abstract).is_some();
+            // Private members are declared like any other member under their qualified name.
+            if let Some(mut name) = self.private_name() {
+                name.token_type = TokenType::Identifier;
+                self.tokens.push_front(name);
+            }
+            if field {
+                fields.push(self.field());
+                continue;
+            }
+            if abstract_method {
+                abstracts.push(self.abstract_method());
+                continue;
+            }
+            match self.peek_next().token_type {
+                TokenType::LeftBrace | TokenType::Colon => getters.push(self.getter()),
+                TokenType::Equal => setters.push(self.setter()),
+                _ => methods.push(self.function("method"))
+            }
         }
+        (methods, getters, setters, fields, abstracts)
+    }
 
-        self.consume(&TokenType::RightBrace).expect("Expect '}' after class body");
-        Stmt::Class(Box::from(name), Box::from(superclass), methods)
+    fn field(&mut self) -> Stmt {
+        let name = self.consume(&TokenType::Identifier).expect("Expect field name.");
+        let annotation = self.annotation();
+        let initializer = match self.consume(&TokenType::Equal) {
+            Some(_) => self.expression(),
+            None => Expr::new(ExprType::Literal(Object::Nil))
+        };
+        self.consume(&TokenType::Semicolon).expect("Expect ';' after field declaration.");
+        Stmt::Var(Box::from(name), Box::from(initializer), annotation)
+    }
 
+    fn abstract_method(&mut self) -> Stmt {
+        let name = self.consume(&TokenType::Identifier).expect("Expect method name.");
+        self.consume(&TokenType::LeftParen).expect("Expect '(' after method name.");
+        let parameters = self.parameters();
+        self.consume(&TokenType::RightParen).expect("Expect ')' after parameters.");
+        let returns = self.annotation();
+        self.consume(&TokenType::Semicolon).expect("Expect ';' after abstract method.");
+        Stmt::Function(Box::from(name), Rc::new(parameters), Rc::new(Vec::new()), false, returns)
     }
 
     fn function(&mut self, kind: &str) -> Stmt {
         let name = self.consume(&TokenType::Identifier).expect(format!("Expect {} name.", kind).as_str());
         self.consume(&TokenType::LeftParen).expect(format!("Expect '(' after {} name.", kind).as_str());
+        let parameters = self.parameters();
+        self.consume(&TokenType::RightParen).expect("Expect ')' after parameters.");
+        let returns = self.annotation();
+        self.consume(&TokenType::LeftBrace).expect(format!("Expect '{{' before {} body.", kind).as_str());
+        let (body, generator) = self.function_body();
+        Stmt::Function(Box::from(name), Rc::new(parameters), Rc::new(body), generator, returns)
+    }
+
+    /// Parses an optional `: Type` or `: Type?` annotation.
+    fn annotation(&mut self) -> Option<TypeAnnotation> {
+        self.consume(&TokenType::Colon)?;
+        let name = match self.consume(&TokenType::Nil) {
+            Some(name) => name,
+            None => self.consume(&TokenType::Identifier).expect("Expect type name after ':'.")
+        };
+        let nullable = self.consume(&TokenType::Question).is_some();
+        Some(TypeAnnotation { name, nullable })
+    }
 
-        let mut parameters = Vec::new();
+    fn parameters(&mut self) -> Vec<Parameter> {
+        let mut parameters: Vec<Parameter> = Vec::new();
         if !self.check(&TokenType::RightParen) {
             loop {
                 if parameters.len() >= 255 {
                     error(self.peek().line, "Expect parameter name.".to_string());
                 }
 
-                parameters.push(self.consume(&TokenType::Identifier).expect("Expect parameter name."));
+                let rest = self.consume(&TokenType::DotDotDot).is_some();
+                let name = self.consume(&TokenType::Identifier).expect("Expect parameter name.");
+                let annotation = self.annotation();
+                let default = if !rest && self.consume(&TokenType::Equal).is_some() {
+                    Some(self.expression())
+                } else {
+                    None
+                };
+
+                if default.is_none() && !rest && parameters.last().is_some_and(|last| last.default.is_some()) {
+                    report(name.line, format!("at {}", name.lexeme), String::from("Parameters after a default value need a default value too."));
+                    panic!("Parameters after a default value need a default value too.");
+                }
+                parameters.push(Parameter { name, default, rest, annotation });
+
+                if rest && !self.check(&TokenType::RightParen) {
+                    report(self.peek().line, format!("at {}", self.peek().lexeme), String::from("Rest parameter must be the last parameter."));
+                    panic!("Rest parameter must be the last parameter.");
+                }
 
                 if !self.check(&TokenType::Comma) {
                     break;
@@ -84,15 +281,48 @@ impl Parser {
                 }
             }
         }
-        
-        self.consume(&TokenType::RightParen).expect("Expect ')' after parameters.");
-        self.consume(&TokenType::LeftBrace).expect(format!("Expect '{{' before {} body.", kind).as_str());
+        parameters
+    }
+
+    /// Parses a function body and reports whether it is a generator, which is
+    /// the case when the body itself (not a nested function) contains a `yield`.
+    fn function_body(&mut self) -> (Vec<Stmt>, bool) {
+        self.generators.push(false);
         let body = self.block();
-        Stmt::Function(Box::from(name), parameters, Rc::new(body))
+        (body, self.generators.pop().unwrap())
+    }
+
+    fn getter(&mut self) -> Stmt {
+        let name = self.consume(&TokenType::Identifier).expect("Expect getter name.");
+        let returns = self.annotation();
+        self.consume(&TokenType::LeftBrace).expect("Expect '{' before getter body.");
+        let (body, generator) = self.function_body();
+        Stmt::Function(Box::from(name), Rc::new(Vec::new()), Rc::new(body), generator, returns)
+    }
+
+    fn setter(&mut self) -> Stmt {
+        let name = self.consume(&TokenType::Identifier).expect("Expect setter name.");
+        self.consume(&TokenType::Equal).expect("Expect '=' after setter name.");
+        self.consume(&TokenType::LeftParen).expect("Expect '(' after '='.");
+        let parameter = Parameter {
+            name: self.consume(&TokenType::Identifier).expect("Expect setter parameter name."),
+            default: None,
+            rest: false,
+            annotation: self.annotation()
+        };
+        self.consume(&TokenType::RightParen).expect("Expect ')' after setter parameter.");
+        self.consume(&TokenType::LeftBrace).expect("Expect '{' before setter body.");
+        let (body, generator) = self.function_body();
+        Stmt::Function(Box::from(name), Rc::new(vec![parameter]), Rc::new(body), generator, None)
     }
 
     fn var_declaration(&mut self) -> Stmt {
+        if self.check(&TokenType::LeftBracket) || self.check(&TokenType::LeftBrace) {
+            return self.destructuring_declaration();
+        }
+
         let name = self.consume(&TokenType::Identifier).expect("Expect variable name.");
+        let annotation = self.annotation();
 
         let initializer = if self.check(&TokenType::Equal) {
             self.advance();
@@ -102,30 +332,90 @@ impl Parser {
         };
 
         self.consume(&TokenType::Semicolon).expect("Expect ';' after variable declaration.");
-        Stmt::Var(Box::new(name), Box::new(initializer))
+        Stmt::Var(Box::new(name), Box::new(initializer), annotation)
+    }
+
+    fn destructuring_declaration(&mut self) -> Stmt {
+        let destructure = if self.consume(&TokenType::LeftBracket).is_some() {
+            let mut names = Vec::new();
+            let mut rest = None;
+            while !self.check(&TokenType::RightBracket) {
+                if self.consume(&TokenType::DotDotDot).is_some() {
+                    rest = Some(self.consume(&TokenType::Identifier).expect("Expect name after '...'."));
+                    break;
+                }
+                names.push(self.consume(&TokenType::Identifier).expect("Expect variable name."));
+                if self.consume(&TokenType::Comma).is_none() {
+                    break;
+                }
+            }
+            self.consume(&TokenType::RightBracket).expect("Expect ']' after destructured names.");
+            Destructure::List(names, rest)
+        } else {
+            self.consume(&TokenType::LeftBrace);
+            let mut names = Vec::new();
+            while !self.check(&TokenType::RightBrace) {
+                names.push(self.consume(&TokenType::Identifier).expect("Expect variable name."));
+                if self.consume(&TokenType::Comma).is_none() {
+                    break;
+                }
+            }
+            self.consume(&TokenType::RightBrace).expect("Expect '}' after destructured names.");
+            Destructure::Fields(names)
+        };
+
+        self.consume(&TokenType::Equal).expect("Expect '=' after destructuring pattern.");
+        let initializer = self.expression();
+        self.consume(&TokenType::Semicolon).expect("Expect ';' after variable declaration.");
+        Stmt::VarDestructure(Box::new(destructure), Box::new(initializer))
+    }
+
+    fn const_declaration(&mut self) -> Stmt {
+        let name = self.consume(&TokenType::Identifier).expect("Expect constant name.");
+        let annotation = self.annotation();
+        self.consume(&TokenType::Equal).expect("Expect '=' after constant name.");
+        let initializer = self.expression();
+        self.consume(&TokenType::Semicolon).expect("Expect ';' after constant declaration.");
+        Stmt::Const(Box::new(name), Box::new(initializer), annotation)
     }
 
     fn statement(&mut self) -> Stmt {
         match self.peek().token_type {
             TokenType::For => {
-                self.advance();
-                self.for_statement()
+                let keyword = self.advance();
+                self.for_statement(keyword)
             },
             TokenType::If => {
                 self.advance();
                 self.if_statement()
             },
             TokenType::Print => {
-                self.consume(&TokenType::Print);
-                self.print_statement()
+                let keyword = self.advance();
+                self.print_statement(keyword)
             },
             TokenType::Return => {
-                self.advance();
-                self.return_statement()
+                let keyword = self.advance();
+                self.return_statement(keyword)
             },
             TokenType::While => {
+                let keyword = self.advance();
+                self.while_statement(keyword)
+            },
+            TokenType::Throw => {
+                let keyword = self.advance();
+                self.throw_statement(keyword)
+            },
+            TokenType::Try => {
                 self.advance();
-                self.while_statement()
+                self.try_statement()
+            },
+            TokenType::Yield => {
+                let keyword = self.advance();
+                self.yield_statement(keyword)
+            },
+            TokenType::Match => {
+                let keyword = self.advance();
+                self.match_statement(keyword)
             },
             TokenType::LeftBrace => {
                 self.advance();
@@ -135,8 +425,12 @@ impl Parser {
         }
     }
 
-    fn for_statement(&mut self) -> Stmt {
+    fn for_statement(&mut self, keyword: Token) -> Stmt {
         self.consume(&TokenType::LeftParen).expect("Expect '(' after 'for'.");
+
+        if self.check(&TokenType::Var) && self.tokens.get(2).is_some_and(|token| token.token_type == TokenType::In) {
+            return self.for_in_statement();
+        }
         let initializer = match self.peek().token_type {
             TokenType::Semicolon => {
                 self.advance(); 
@@ -172,7 +466,7 @@ impl Parser {
             body = Stmt::Block(vec![body, Stmt::Expression(Box::from(increment.unwrap()))]);
         }
 
-        body = Stmt::While(Box::from(condition), Box::from(body));
+        body = Stmt::While(Box::from(keyword), Box::from(condition), Box::from(body));
 
         if initializer.is_some() {
             body = Stmt::Block(vec![initializer.unwrap(), body]);
@@ -181,6 +475,17 @@ impl Parser {
         body
     }
 
+    fn for_in_statement(&mut self) -> Stmt {
+        self.consume(&TokenType::Var);
+        let name = self.consume(&TokenType::Identifier).expect("Expect loop variable name.");
+        self.consume(&TokenType::In).expect("Expect 'in' after loop variable.");
+        let iterable = self.expression();
+        self.consume(&TokenType::RightParen).expect("Expect ')' after 'for' clause.");
+        let body = self.statement();
+
+        Stmt::ForIn(Box::from(name), Box::from(iterable), Box::from(body))
+    }
+
     fn if_statement(&mut self) -> Stmt {
         self.consume(&TokenType::LeftParen).expect("Expect '(' after 'if'.");
         let condition = self.expression();
@@ -197,13 +502,71 @@ impl Parser {
         Stmt::If(Box::from(condition), Box::from(then_branch), Box::from(else_branch))
     }
 
-    fn while_statement(&mut self) -> Stmt {
+    fn match_statement(&mut self, keyword: Token) -> Stmt {
+        self.consume(&TokenType::LeftParen).expect("Expect '(' after 'match'.");
+        let subject = self.expression();
+        self.consume(&TokenType::RightParen).expect("Expect ')' after match value.");
+        self.consume(&TokenType::LeftBrace).expect("Expect '{' before match cases.");
+
+        let mut cases = Vec::new();
+        while !self.check(&TokenType::RightBrace) && !self.is_at_end() {
+            self.consume(&TokenType::Case).expect("Expect 'case' in match.");
+            let mut patterns = vec![self.pattern()];
+            while self.consume(&TokenType::Comma).is_some() {
+                patterns.push(self.pattern());
+            }
+            let guard = self.consume(&TokenType::If).map(|_| self.expression());
+            self.consume(&TokenType::EqualGreater).expect("Expect '=>' after case pattern.");
+            let body = self.statement();
+            cases.push(Case { patterns, guard, body });
+        }
+
+        self.consume(&TokenType::RightBrace).expect("Expect '}' after match cases.");
+        Stmt::Match(Box::from(keyword), Box::from(subject), cases)
+    }
+
+    fn pattern(&mut self) -> Pattern {
+        if self.consume(&TokenType::LeftBracket).is_some() {
+            let mut elements = Vec::new();
+            let mut rest = None;
+            while !self.check(&TokenType::RightBracket) {
+                if self.consume(&TokenType::DotDotDot).is_some() {
+                    rest = Some(self.consume(&TokenType::Identifier).expect("Expect name after '...'."));
+                    break;
+                }
+                elements.push(self.pattern());
+                if self.consume(&TokenType::Comma).is_none() {
+                    break;
+                }
+            }
+            self.consume(&TokenType::RightBracket).expect("Expect ']' after list pattern.");
+            return Pattern::List(elements, rest);
+        }
+
+        if self.check(&TokenType::Identifier) {
+            match self.peek_next().token_type {
+                TokenType::Identifier => {
+                    let class = Expr::new(ExprType::Variable(Box::from(self.advance())));
+                    return Pattern::Class(class, self.advance());
+                },
+                TokenType::Dot => return Pattern::Value(self.call()),
+                _ => {
+                    let name = self.advance();
+                    return if name.lexeme == "_" { Pattern::Wildcard } else { Pattern::Binding(name) };
+                }
+            }
+        }
+
+        Pattern::Value(self.unary())
+    }
+
+    fn while_statement(&mut self, keyword: Token) -> Stmt {
         self.consume(&TokenType::LeftParen).expect("Expect '(' after 'while'.");
         let condition = self.expression();
         self.consume(&TokenType::RightParen).expect("Expect ')' after condition.");
         let body = self.statement();
 
-        Stmt::While(Box::from(condition), Box::from(body))
+        Stmt::While(Box::from(keyword), Box::from(condition), Box::from(body))
     }
 
     fn block(&mut self) -> Vec<Stmt> {
@@ -217,13 +580,13 @@ impl Parser {
         statements
     }
 
-    fn print_statement(&mut self) -> Stmt {
+    fn print_statement(&mut self, keyword: Token) -> Stmt {
         let expr = self.expression();
         self.consume(&TokenType::Semicolon).expect("Expect ';' after value.");
-        Stmt::Print(Box::new(expr))
+        Stmt::Print(Box::new(keyword), Box::new(expr))
     }
 
-    fn return_statement(&mut self) -> Stmt {
+    fn return_statement(&mut self, keyword: Token) -> Stmt {
         let value = if !self.check(&TokenType::Semicolon) {
             Some(self.expression())
         } else {
@@ -231,21 +594,101 @@ impl Parser {
         };
 
         self.consume(&TokenType::Semicolon).expect("Expect ';' after return value.");
-        Stmt::Return(Box::from(value))
+        Stmt::Return(Box::from(keyword), Box::from(value))
+    }
+
+    fn throw_statement(&mut self, keyword: Token) -> Stmt {
+        let value = self.expression();
+        self.consume(&TokenType::Semicolon).expect("Expect ';' after thrown value.");
+        Stmt::Throw(Box::from(keyword), Box::from(value))
+    }
+
+    fn yield_statement(&mut self, keyword: Token) -> Stmt {
+        let value = if !self.check(&TokenType::Semicolon) {
+            self.expression()
+        } else {
+            Expr::new(ExprType::Literal(Object::Nil))
+        };
+
+        self.consume(&TokenType::Semicolon).expect("Expect ';' after yielded value.");
+        if let Some(generator) = self.generators.last_mut() {
+            *generator = true;
+        }
+        Stmt::Yield(Box::from(keyword), Box::from(value))
+    }
+
+    fn try_statement(&mut self) -> Stmt {
+        self.consume(&TokenType::LeftBrace).expect("Expect '{' after 'try'.");
+        let body = self.block();
+
+        let catch_clause = if self.consume(&TokenType::Catch).is_some() {
+            self.consume(&TokenType::LeftParen).expect("Expect '(' after 'catch'.");
+            let name = self.consume(&TokenType::Identifier).expect("Expect exception variable name.");
+            self.consume(&TokenType::RightParen).expect("Expect ')' after exception variable.");
+            self.consume(&TokenType::LeftBrace).expect("Expect '{' before catch body.");
+            Some((name, self.block()))
+        } else {
+            None
+        };
+
+        let finally_clause = if self.consume(&TokenType::Finally).is_some() {
+            self.consume(&TokenType::LeftBrace).expect("Expect '{' after 'finally'.");
+            Some(self.block())
+        } else {
+            None
+        };
+
+        if catch_clause.is_none() && finally_clause.is_none() {
+            report(self.peek().line, format!("at {}", self.peek().lexeme), String::from("Expect 'catch' or 'finally' after try block."));
+            panic!("Expect 'catch' or 'finally' after try block.");
+        }
+
+        Stmt::Try(body, Box::from(catch_clause), Box::from(finally_clause))
     }
 
     fn expressions_statement(&mut self) -> Stmt {
         let expr = self.expression();
+        if self.check(&TokenType::Comma) {
+            return self.multiple_assignment(expr);
+        }
         self.consume(&TokenType::Semicolon).expect("Expect ';' after expression.");
         Stmt::Expression(Box::new(expr))
     }
 
+    /// Parses `a, b = b, a;`. A single value on the right is destructured as a list.
+    fn multiple_assignment(&mut self, first: Expr) -> Stmt {
+        let mut targets = vec![first];
+        while self.consume(&TokenType::Comma).is_some() {
+            targets.push(self.call());
+        }
+
+        let equals = self.consume(&TokenType::Equal).expect("Expect '=' after assignment targets.");
+        for target in &targets {
+            if !matches!(target.expr_type, ExprType::Variable(_) | ExprType::Get(_, _) | ExprType::Index(_, _, _)) {
+                panic!("{}, Invalid assign target.", equals.lexeme);
+            }
+        }
+
+        let mut values = vec![self.expression()];
+        while self.consume(&TokenType::Comma).is_some() {
+            values.push(self.expression());
+        }
+
+        if values.len() != 1 && values.len() != targets.len() {
+            report(equals.line, format!("at {}", equals.lexeme), format!("Expect {} values but got {}.", targets.len(), values.len()));
+            panic!("Expect {} values but got {}.", targets.len(), values.len());
+        }
+
+        self.consume(&TokenType::Semicolon).expect("Expect ';' after assignment.");
+        Stmt::Assign(targets, Box::from(equals), values)
+    }
+
     fn expression(&mut self) -> Expr {
         self.assignment()
     }
 
     fn assignment(&mut self) -> Expr {
-        let expr = self.or();
+        let expr = self.conditional();
 
         if self.matching(&[TokenType::Equal]) {
             let equals = self.advance();
@@ -254,10 +697,67 @@ impl Parser {
             return match expr.expr_type {
                 ExprType::Variable(name) => Expr::new(ExprType::Assign(name, Box::from(value))),
                 ExprType::Get(expr, name) => Expr::new(ExprType::Set(expr, name, Box::from(value))),
+                ExprType::Index(expr, bracket, index) => Expr::new(ExprType::SetIndex(expr, bracket, index, Box::from(value))),
                 _ => panic!("{}, Invalid assign target.", equals.lexeme)
             }
         }
 
+        if self.matching(&[TokenType::PlusEqual, TokenType::MinusEqual, TokenType::StarEqual, TokenType::SlashEqual, TokenType::PercentEqual]) {
+            let operator = self.advance();
+            let value = self.assignment();
+            return self.compound_assignment(expr, operator, value, false);
+        }
+
+        expr
+    }
+
+    /// Builds the assignment for `+=` style operators and increments. The operator
+    /// token is turned into the binary operator applied to the current value.
+    fn compound_assignment(&mut self, target: Expr, operator: Token, value: Expr, postfix: bool) -> Expr {
+        let (token_type, lexeme) = match operator.token_type {
+            TokenType::PlusEqual | TokenType::PlusPlus => (TokenType::Plus, "+"),
+            TokenType::MinusEqual | TokenType::MinusMinus => (TokenType::Minus, "-"),
+            TokenType::StarEqual => (TokenType::Star, "*"),
+            TokenType::SlashEqual => (TokenType::Slash, "/"),
+            TokenType::PercentEqual => (TokenType::Percent, "%"),
+            _ => panic!("{}, Invalid compound assignment operator.", operator.lexeme)
+        };
+        let op = Box::from(Token::new(token_type, String::from(lexeme), String::new(), operator.line));
+
+        match target.expr_type {
+            ExprType::Variable(name) => Expr::new(ExprType::CompoundAssign(name, op, Box::from(value), postfix)),
+            ExprType::Get(object, name) => Expr::new(ExprType::CompoundSet(object, name, op, Box::from(value), postfix)),
+            ExprType::Index(object, bracket, index) => Expr::new(ExprType::CompoundSetIndex(object, bracket, index, op, Box::from(value), postfix)),
+            _ => {
+                report(operator.line, format!("at {}", operator.lexeme), String::from("Invalid assignment target."));
+                panic!("Invalid assignment target.");
+            }
+        }
+    }
+
+    fn conditional(&mut self) -> Expr {
+        let expr = self.coalesce();
+
+        if self.check(&TokenType::Question) {
+            self.advance();
+            let then_branch = self.expression();
+            self.consume(&TokenType::Colon).expect("Expect ':' after then branch of conditional expression.");
+            let else_branch = self.conditional();
+            return Expr::new(ExprType::Conditional(Box::from(expr), Box::from(then_branch), Box::from(else_branch)));
+        }
+
+        expr
+    }
+
+    fn coalesce(&mut self) -> Expr {
+        let mut expr = self.or();
+
+        while self.check(&TokenType::QuestionQuestion) {
+            let operator = self.advance();
+            let right = self.or();
+            expr = Expr::new(ExprType::Logical(Box::from(expr), Box::from(operator), Box::from(right)));
+        }
+
         expr
     }
 
@@ -298,9 +798,77 @@ impl Parser {
     }
 
     fn comparison(&mut self) -> Expr {
+        let mut expr = self.range();
+
+        while self.matching(&[TokenType::Greater, TokenType::GreaterEqual, TokenType::Less, TokenType::LessEqual, TokenType::Is]) {
+            let operator = self.advance();
+            let right = self.range();
+            expr = Expr::new(ExprType::Binary(Box::from(expr), Box::from(operator), Box::from(right)));
+        }
+
+        expr
+    }
+
+    /// Parses `start..end` and `start..=end`, optionally followed by `by step`.
+    /// `by` is only special after a range, so it stays usable as a name.
+    fn range(&mut self) -> Expr {
+        let start = self.bit_or();
+
+        if self.matching(&[TokenType::DotDot, TokenType::DotDotEqual]) {
+            let operator = self.advance();
+            let end = self.bit_or();
+            let step = if self.check(&TokenType::Identifier) && self.peek().lexeme == "by" {
+                self.advance();
+                Some(Box::from(self.bit_or()))
+            } else {
+                None
+            };
+            return Expr::new(ExprType::Range(Box::from(start), Box::from(operator), Box::from(end), step));
+        }
+
+        start
+    }
+
+    fn bit_or(&mut self) -> Expr {
+        let mut expr = self.bit_xor();
+
+        while self.matching(&[TokenType::Pipe]) {
+            let operator = self.advance();
+            let right = self.bit_xor();
+            expr = Expr::new(ExprType::Binary(Box::from(expr), Box::from(operator), Box::from(right)));
+        }
+
+        expr
+    }
+
+    fn bit_xor(&mut self) -> Expr {
+        let mut expr = self.bit_and();
+
+        while self.matching(&[TokenType::Caret]) {
+            let operator = self.advance();
+            let right = self.bit_and();
+            expr = Expr::new(ExprType::Binary(Box::from(expr), Box::from(operator), Box::from(right)));
+        }
+
+        expr
+    }
+
+    fn bit_and(&mut self) -> Expr {
+        let mut expr = self.shift();
+
+        while self.matching(&[TokenType::Ampersand]) {
+            let operator = self.advance();
+            let right = self.shift();
+            expr = Expr::new(ExprType::Binary(Box::from(expr), Box::from(operator), Box::from(right)));
+        }
+
+        expr
+    }
+
+    fn shift(&mut self) -> Expr {
         let mut expr = self.term();
 
-        while self.matching(&[TokenType::Greater, TokenType::GreaterEqual, TokenType::Less, TokenType::LessEqual]) {
+        while self.matching(&[TokenType::LessLess, TokenType::GreaterGreater]) {
             let operator = self.advance();
             let right = self.term();
             expr = Expr::new(ExprType::Binary(Box::from(expr), Box::from(operator), Box::from(right)));
@@ -324,7 +892,7 @@ impl Parser {
     fn factor(&mut self) -> Expr {
         let mut expr = self.unary();
 
-        while self.matching(&[TokenType::Slash, TokenType::Star]) {
+        while self.matching(&[TokenType::Slash, TokenType::Star, TokenType::Percent, TokenType::TildeSlash]) {
             let operator = self.advance();
             let right = self.unary();
             expr = Expr::new(ExprType::Binary(Box::from(expr), Box::from(operator), Box::from(right)));
@@ -334,17 +902,49 @@ impl Parser {
     }
 
     fn unary(&mut self) -> Expr {
-        if self.matching(&[TokenType::Bang, TokenType::Minus]) {
+        if self.matching(&[TokenType::Bang, TokenType::Minus, TokenType::Tilde, TokenType::TypeOf]) {
             let operator = self.advance();
             let right = self.unary();
             return Expr::new(ExprType::Unary(Box::from(operator), Box::from(right)));
         }
 
-        return self.call();
+        if self.matching(&[TokenType::PlusPlus, TokenType::MinusMinus]) {
+            let operator = self.advance();
+            let target = self.unary();
+            let one = Expr::new(ExprType::Literal(Object::Number(1.0)));
+            return self.compound_assignment(target, operator, one, false);
+        }
+
+        self.exponent()
+    }
+
+    fn exponent(&mut self) -> Expr {
+        let expr = self.postfix();
+
+        if self.matching(&[TokenType::StarStar]) {
+            let operator = self.advance();
+            let right = self.unary();
+            return Expr::new(ExprType::Binary(Box::from(expr), Box::from(operator), Box::from(right)));
+        }
+
+        expr
+    }
+
+    fn postfix(&mut self) -> Expr {
+        let expr = self.call();
+
+        if self.matching(&[TokenType::PlusPlus, TokenType::MinusMinus]) {
+            let operator = self.advance();
+            let one = Expr::new(ExprType::Literal(Object::Number(1.0)));
+            return self.compound_assignment(expr, operator, one, true);
+        }
+
+        expr
     }
 
     fn call(&mut self) -> Expr {
         let mut expr = self.primary();
+        let mut optional = false;
 
         loop {
             if self.check(&TokenType::LeftParen) {
@@ -352,16 +952,49 @@ impl Parser {
                 expr = self.finish_call(expr);
             } else if self.check(&TokenType::Dot) {
                 self.advance();
-                let name = self.consume(&TokenType::Identifier).expect("Expect property name after '.'.");
+                // Keywords can't name properties, except for the built-in `Fiber.yield`.
+                let name = match self.consume(&TokenType::Yield).or_else(|| self.private_name()) {
+                    Some(name) => name,
+                    None => self.consume(&TokenType::Identifier).expect("Expect property name after '.'.")
+                };
                 expr = Expr::new(ExprType::Get(Box::from(expr), Box::from(name)));
+            } else if self.check(&TokenType::LeftBracket) {
+                let bracket = self.advance();
+                let index = self.expression();
+                self.consume(&TokenType::RightBracket).expect("Expect ']' after index.");
+                expr = Expr::new(ExprType::Index(Box::from(expr), Box::from(bracket), Box::from(index)));
+            } else if self.check(&TokenType::QuestionDot) {
+                self.advance();
+                let name = match self.private_name() {
+                    Some(name) => name,
+                    None => self.consume(&TokenType::Identifier).expect("Expect property name after '?.'.")
+                };
+                expr = Expr::new(ExprType::OptionalGet(Box::from(expr), Box::from(name)));
+                optional = true;
             } else {
                 break;
             }
         }
 
+        // The whole chain evaluates to nil as soon as an optional access hits nil.
+        if optional {
+            expr = Expr::new(ExprType::OptionalChain(Box::from(expr)));
+        }
+
         expr
     }
 
+    /// Consumes a `#name`. Private names are qualified with the id of the
+    /// declaration they appear in, so classes in a hierarchy can't see or clash
+    /// with each other's, even if they share a name.
+    fn private_name(&mut self) -> Option<Token> {
+        let mut name = self.consume(&TokenType::PrivateName)?;
+        if let Some(class) = self.classes.last() {
+            name.lexeme = format!("{}.{}", class, name.lexeme);
+        }
+        Some(name)
+    }
+
     fn finish_call(&mut self, callee: Expr) -> Expr {
         let mut arguments = Vec::new();
         if !self.check(&TokenType::RightParen) {
@@ -406,6 +1039,20 @@ impl Parser {
                 let token = self.advance();
                 Expr::new(ExprType::Literal(Object::String(token.lexeme)))
             },
+            TokenType::LeftBracket => {
+                self.advance();
+                let mut elements = Vec::new();
+                if !self.check(&TokenType::RightBracket) {
+                    loop {
+                        elements.push(self.expression());
+                        if self.consume(&TokenType::Comma).is_none() {
+                            break;
+                        }
+                    }
+                }
+                self.consume(&TokenType::RightBracket).expect("Expect ']' after list elements.");
+                Expr::new(ExprType::List(elements))
+            },
             TokenType::LeftParen => {
                 self.advance();
                 let expr = self.expression();
@@ -461,6 +1108,13 @@ impl Parser {
         self.peek().token_type == TokenType::Eof
     }
 
+    fn peek_next(&self) -> &Token {
+        match self.tokens.get(1) {
+            Some(token) => token,
+            None => self.peek()
+        }
+    }
+
     fn peek(&self) -> &Token {
         match self.tokens.get(0) {
             Some(token) => token,
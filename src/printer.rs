@@ -0,0 +1,185 @@
+use crate::{expr::{Expr, ExprType}, object::Object, stmt::{Destructure, Parameter, Pattern, Stmt}, token::{source_name, Token}};
+
+/// Prints a program as nested s-expressions, one statement per line with
+/// nested statements indented.
+pub fn print_ast(statements: &[Stmt]) -> String {
+    statements.iter().map(statement).collect::<Vec<String>>().join("\n")
+}
+
+/// A parenthesized node whose children are statements on their own lines.
+fn node(head: String, children: Vec<String>) -> String {
+    if children.is_empty() {
+        return format!("({})", head);
+    }
+    let children: Vec<String> = children.iter()
+        .flat_map(|child| child.lines().map(|line| format!("  {}", line)).collect::<Vec<String>>())
+        .collect();
+    format!("({}\n{})", head, children.join("\n"))
+}
+
+fn statements(statements: &[Stmt]) -> Vec<String> {
+    statements.iter().map(statement).collect()
+}
+
+fn names(names: &[Token]) -> String {
+    names.iter().map(|name| name.lexeme.clone()).collect::<Vec<String>>().join(" ")
+}
+
+fn parameters(parameters: &[Parameter]) -> String {
+    let parameters: Vec<String> = parameters.iter().map(|parameter| match (&parameter.default, parameter.rest) {
+        (_, true) => format!("...{}", parameter.name.lexeme),
+        (Some(default), _) => format!("(= {} {})", parameter.name.lexeme, expression(default)),
+        (None, _) => parameter.name.lexeme.clone()
+    }).collect();
+    format!("({})", parameters.join(" "))
+}
+
+fn pattern(pattern: &Pattern) -> String {
+    match pattern {
+        Pattern::Wildcard => String::from("_"),
+        Pattern::Binding(name) => name.lexeme.clone(),
+        Pattern::Value(value) => expression(value),
+        Pattern::Class(class, name) => format!("({} {})", expression(class), name.lexeme),
+        Pattern::List(elements, rest) => {
+            let mut elements: Vec<String> = elements.iter().map(self::pattern).collect();
+            elements.extend(rest.iter().map(|rest| format!("...{}", rest.lexeme)));
+            format!("[{}]", elements.join(" "))
+        }
+    }
+}
+
+fn statement(stmt: &Stmt) -> String {
+    match stmt {
+        Stmt::Assign(targets, _, values) => {
+            let targets: Vec<String> = targets.iter().map(expression).collect();
+            let values: Vec<String> = values.iter().map(expression).collect();
+            format!("(= ({}) ({}))", targets.join(" "), values.join(" "))
+        },
+        Stmt::Block(body) => node(String::from("block"), statements(body)),
+        Stmt::Class(name, superclass, traits, methods, getters, setters, fields, abstracts) => {
+            let mut head = format!("class {}", name.lexeme);
+            if let Some(superclass) = superclass.as_ref() {
+                head += &format!(" < {}", expression(superclass));
+            }
+            if !traits.is_empty() {
+                head += &format!(" with {}", traits.iter().map(expression).collect::<Vec<String>>().join(" "));
+            }
+            let mut members = statements(fields);
+            members.extend(abstracts.iter().map(|method| format!("(abstract {})", statement(method))));
+            members.extend(statements(methods));
+            members.extend(getters.iter().map(|getter| format!("(get {})", statement(getter))));
+            members.extend(setters.iter().map(|setter| format!("(set {})", statement(setter))));
+            node(head, members)
+        },
+        Stmt::Const(name, initializer, _) => format!("(const {} {})", name.lexeme, expression(initializer)),
+        Stmt::Export(declaration) => node(String::from("export"), vec![statement(declaration)]),
+        Stmt::Enum(name, members) => format!("(enum {} {})", name.lexeme, names(members)),
+        Stmt::Expression(expr) => expression(expr),
+        Stmt::ForIn(name, iterable, body) => node(format!("for {} in {}", name.lexeme, expression(iterable)), vec![statement(body)]),
+        Stmt::Function(name, parameters, body, generator, _) => {
+            let kind = if *generator { "generator" } else { "fun" };
+            node(format!("{} {} {}", kind, source_name(&name.lexeme), self::parameters(parameters)), statements(body))
+        },
+        Stmt::If(condition, then_branch, else_branch) => {
+            let mut branches = vec![statement(then_branch)];
+            branches.extend(else_branch.as_ref().as_ref().map(statement));
+            node(format!("if {}", expression(condition)), branches)
+        },
+        Stmt::Import(path, imported) => match imported {
+            Some(imported) => format!("(import \"{}\" {})", path.lexeme, names(imported)),
+            None => format!("(import \"{}\")", path.lexeme)
+        },
+        Stmt::Match(_, subject, cases) => {
+            let cases = cases.iter().map(|case| {
+                let mut head = format!("case {}", case.patterns.iter().map(pattern).collect::<Vec<String>>().join(" "));
+                if let Some(guard) = &case.guard {
+                    head += &format!(" if {}", expression(guard));
+                }
+                node(head, vec![statement(&case.body)])
+            }).collect();
+            node(format!("match {}", expression(subject)), cases)
+        },
+        Stmt::Print(_, expr) => format!("(print {})", expression(expr)),
+        Stmt::Return(_, value) => match value.as_ref() {
+            Some(value) => format!("(return {})", expression(value)),
+            None => String::from("(return)")
+        },
+        Stmt::Throw(_, expr) => format!("(throw {})", expression(expr)),
+        Stmt::Trait(name, methods, getters, setters) => {
+            let mut members = statements(methods);
+            members.extend(getters.iter().map(|getter| format!("(get {})", statement(getter))));
+            members.extend(setters.iter().map(|setter| format!("(set {})", statement(setter))));
+            node(format!("trait {}", name.lexeme), members)
+        },
+        Stmt::Try(body, catch, finally) => {
+            let mut clauses = vec![node(String::from("body"), statements(body))];
+            if let Some((name, catch)) = catch.as_ref() {
+                clauses.push(node(format!("catch {}", name.lexeme), statements(catch)));
+            }
+            if let Some(finally) = finally.as_ref() {
+                clauses.push(node(String::from("finally"), statements(finally)));
+            }
+            node(String::from("try"), clauses)
+        },
+        Stmt::Var(name, initializer, _) => format!("(var {} {})", source_name(&name.lexeme), expression(initializer)),
+        Stmt::VarDestructure(destructure, initializer) => {
+            let names = match destructure.as_ref() {
+                Destructure::List(names, Some(rest)) => format!("[{} ...{}]", self::names(names), rest.lexeme),
+                Destructure::List(names, None) => format!("[{}]", self::names(names)),
+                Destructure::Fields(names) => format!("{{{}}}", self::names(names))
+            };
+            format!("(var {} {})", names, expression(initializer))
+        },
+        Stmt::While(_, condition, body) => node(format!("while {}", expression(condition)), vec![statement(body)]),
+        Stmt::Yield(_, expr) => format!("(yield {})", expression(expr)),
+    }
+}
+
+fn expression(expr: &Expr) -> String {
+    match &expr.expr_type {
+        ExprType::Assign(name, value) => format!("(= {} {})", name.lexeme, expression(value)),
+        ExprType::Binary(left, op, right) | ExprType::Logical(left, op, right) => {
+            format!("({} {} {})", op.lexeme, expression(left), expression(right))
+        },
+        ExprType::Call(callee, _, arguments) => {
+            let mut parts = vec![String::from("call"), expression(callee)];
+            parts.extend(arguments.iter().map(expression));
+            format!("({})", parts.join(" "))
+        },
+        ExprType::CompoundAssign(name, op, value, postfix) => {
+            let position = if *postfix { "postfix " } else { "" };
+            format!("({}{}= {} {})", position, op.lexeme, name.lexeme, expression(value))
+        },
+        ExprType::CompoundSet(object, name, op, value, postfix) => {
+            let position = if *postfix { "postfix " } else { "" };
+            format!("({}{}= (. {} {}) {})", position, op.lexeme, expression(object), source_name(&name.lexeme), expression(value))
+        },
+        ExprType::CompoundSetIndex(object, _, index, op, value, postfix) => {
+            let position = if *postfix { "postfix " } else { "" };
+            format!("({}{}= ([] {} {}) {})", position, op.lexeme, expression(object), expression(index), expression(value))
+        },
+        ExprType::Conditional(condition, then_branch, else_branch) => {
+            format!("(? {} {} {})", expression(condition), expression(then_branch), expression(else_branch))
+        },
+        ExprType::Get(object, name) => format!("(. {} {})", expression(object), source_name(&name.lexeme)),
+        ExprType::Grouping(expr) => format!("(group {})", expression(expr)),
+        ExprType::Index(object, _, index) => format!("([] {} {})", expression(object), expression(index)),
+        ExprType::List(elements) => format!("[{}]", elements.iter().map(expression).collect::<Vec<String>>().join(" ")),
+        ExprType::Literal(Object::String(string)) => format!("\"{}\"", string),
+        ExprType::Literal(value) => value.to_string(),
+        ExprType::OptionalChain(expr) => expression(expr),
+        ExprType::OptionalGet(object, name) => format!("(?. {} {})", expression(object), source_name(&name.lexeme)),
+        ExprType::Range(start, op, end, step) => match step {
+            Some(step) => format!("({} {} {} {})", op.lexeme, expression(start), expression(end), expression(step)),
+            None => format!("({} {} {})", op.lexeme, expression(start), expression(end))
+        },
+        ExprType::Set(object, name, value) => format!("(= (. {} {}) {})", expression(object), source_name(&name.lexeme), expression(value)),
+        ExprType::SetIndex(object, _, index, value) => {
+            format!("(= ([] {} {}) {})", expression(object), expression(index), expression(value))
+        },
+        ExprType::Super(_, method) => format!("(super {})", source_name(&method.lexeme)),
+        ExprType::This(_) => String::from("this"),
+        ExprType::Unary(op, right) => format!("({} {})", op.lexeme, expression(right)),
+        ExprType::Variable(name) => name.lexeme.clone(),
+    }
+}
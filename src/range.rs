@@ -0,0 +1,45 @@
+use std::fmt::Display;
+
+/// A range of numbers from `start` towards `end` in steps of `step`. Its
+/// elements are computed on demand, so large ranges take no memory.
+#[derive(Clone, PartialEq)]
+pub struct Range {
+    pub start: f64,
+    pub end: f64,
+    pub step: f64,
+    pub inclusive: bool,
+}
+
+impl Range {
+
+    pub fn len(&self) -> usize {
+        let span = (self.end - self.start) / self.step;
+        let count = if self.inclusive { span.floor() + 1.0 } else { span.ceil() };
+        if count > 0.0 { count as usize } else { 0 }
+    }
+
+    pub fn get(&self, index: usize) -> f64 {
+        self.start + index as f64 * self.step
+    }
+
+    pub fn contains(&self, n: f64) -> bool {
+        let position = (n - self.start) / self.step;
+        position >= 0.0 && position.fract() == 0.0 && (position as usize) < self.len()
+    }
+
+    pub fn values(&self) -> impl Iterator<Item = f64> + '_ {
+        (0..self.len()).map(|index| self.get(index))
+    }
+
+}
+
+impl Display for Range {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let operator = if self.inclusive { "..=" } else { ".." };
+        write!(f, "{}{}{}", self.start, operator, self.end)?;
+        if self.step != 1.0 {
+            write!(f, " by {}", self.step)?;
+        }
+        Ok(())
+    }
+}
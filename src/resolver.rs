@@ -1,6 +1,6 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
-use crate::{expr::{Expr, ExprType}, token::Token, stmt::Stmt};
+use crate::{expr::{Expr, ExprType}, token::{source_name, Token, TokenType}, stmt::{Destructure, Parameter, Pattern, Stmt}};
 
 pub fn resolve(statements: &Vec<Stmt>) -> HashMap<usize, usize> {
     let mut resolver = Resolver::new();
@@ -20,11 +20,15 @@ enum FunctionType {
 enum ClassType {
     None,
     Class,
-    Subclass
+    Subclass,
+    Trait
 }
 
 struct Resolver {
     scope: Vec<HashMap<String, bool>>,
+    // Names declared with `const`, per scope and at top-level.
+    constants: Vec<HashSet<String>>,
+    global_constants: HashSet<String>,
     locals: HashMap<usize, usize>,
     current_function: FunctionType,
     current_class: ClassType,
@@ -34,6 +38,8 @@ impl Resolver {
     fn new() -> Self {
         Resolver {
             scope: vec![],
+            constants: vec![],
+            global_constants: HashSet::new(),
             locals: HashMap::new(),
             current_function: FunctionType::None,
             current_class: ClassType::None,
@@ -56,10 +62,40 @@ impl Resolver {
 
     fn begin_scope(&mut self) {
         self.scope.push(HashMap::new());
+        self.constants.push(HashSet::new());
     }
 
     fn end_scope(&mut self) {
         self.scope.pop();
+        self.constants.pop();
+    }
+
+    fn define_constant(&mut self, name: &Token) {
+        match self.constants.last_mut() {
+            Some(constants) => constants.insert(name.lexeme.clone()),
+            None => self.global_constants.insert(name.lexeme.clone())
+        };
+    }
+
+    fn check_assignable(&self, name: &Token) {
+        let constant = match self.scope.iter().rposition(|scope| scope.contains_key(&name.lexeme)) {
+            Some(i) => self.constants[i].contains(&name.lexeme),
+            None => self.global_constants.contains(&name.lexeme)
+        };
+
+        if constant {
+            panic!("{} Can't assign to constant.", name.lexeme);
+        }
+    }
+
+    fn check_private(&self, object: &Expr, name: &Token) {
+        if name.token_type != TokenType::PrivateName {
+            return;
+        }
+
+        if self.current_class == ClassType::None || !matches!(object.expr_type, ExprType::This(_)) {
+            panic!("{} Private members can only be accessed through 'this' inside their class.", source_name(&name.lexeme));
+        }
     }
 
     fn declare(&mut self, name: &Token) {
@@ -91,19 +127,36 @@ impl Resolver {
         }
     }
 
-    fn resolve_function(&mut self, parameters: &Vec<Token>, body: &Vec<Stmt>, function_type: FunctionType) {
+    fn resolve_function(&mut self, parameters: &[Parameter], body: &Vec<Stmt>, function_type: FunctionType) {
         let enclosing_function = self.current_function;
         self.current_function = function_type;
         self.begin_scope();
+        // Defaults are evaluated in the function scope and see the parameters before them.
         for parameter in parameters {
-            self.declare(parameter);
-            self.define(parameter);
+            if let Some(default) = &parameter.default {
+                self.resolve_expression(default);
+            }
+            self.declare(&parameter.name);
+            self.define(&parameter.name);
         }
         self.resolve_statements(body);
         self.end_scope();
         self.current_function = enclosing_function;
     }
 
+    fn resolve_pattern(&mut self, pattern: &Pattern) {
+        match pattern {
+            Pattern::Value(value) => self.resolve_expression(value),
+            Pattern::Class(class, _name) => self.resolve_expression(class),
+            Pattern::List(elements, _rest) => {
+                for element in elements {
+                    self.resolve_pattern(element);
+                }
+            },
+            Pattern::Wildcard | Pattern::Binding(_) => {}
+        }
+    }
+
     fn visit_stmt(&mut self, stmt: &Stmt) {
         match stmt {
             Stmt::Block(statements) => {
@@ -111,12 +164,49 @@ impl Resolver {
                 self.resolve_statements(statements);
                 self.end_scope();
             },
-            Stmt::Var(name, initializer) => {
+            Stmt::Var(name, initializer, _) => {
                 self.declare(name);
                 let _ = self.visit_expr(initializer);
                 self.define(name);
+                if self.scope.is_empty() {
+                    self.global_constants.remove(&name.lexeme);
+                }
+            },
+            Stmt::VarDestructure(destructure, initializer) => {
+                self.resolve_expression(initializer);
+                let names = match destructure.as_ref() {
+                    Destructure::List(names, rest) => names.iter().chain(rest.iter()).collect::<Vec<&Token>>(),
+                    Destructure::Fields(names) => names.iter().collect()
+                };
+                for name in names {
+                    self.declare(name);
+                    self.define(name);
+                    if self.scope.is_empty() {
+                        self.global_constants.remove(&name.lexeme);
+                    }
+                }
+            },
+            Stmt::Assign(targets, _equals, values) => {
+                for value in values {
+                    self.resolve_expression(value);
+                }
+                for target in targets {
+                    match &target.expr_type {
+                        ExprType::Variable(name) => {
+                            self.check_assignable(name);
+                            self.resolve_local(target, name);
+                        },
+                        _ => self.resolve_expression(target)
+                    }
+                }
+            },
+            Stmt::Const(name, initializer, _) => {
+                self.declare(name);
+                self.resolve_expression(initializer);
+                self.define(name);
+                self.define_constant(name);
             },
-            Stmt::Function(name, parameters, body) => {
+            Stmt::Function(name, parameters, body, _generator, _) => {
                 self.declare(name);
                 self.define(name);
                 self.resolve_function(parameters, body, FunctionType::Function);
@@ -130,8 +220,8 @@ impl Resolver {
                     None => {},
                 }
             },
-            Stmt::Print(expr) => self.resolve_expression(expr),
-            Stmt::Return(value) => {
+            Stmt::Print(_keyword, expr) => self.resolve_expression(expr),
+            Stmt::Return(_keyword, value) => {
                 if self.current_function == FunctionType::None {
                     panic!("Can't return from top-level code.");
                 }
@@ -146,17 +236,125 @@ impl Resolver {
                     None => {},
                 }
             },
-            Stmt::While(condition, body) => {
+            Stmt::Match(_keyword, subject, cases) => {
+                self.resolve_expression(subject);
+                for case in cases {
+                    for pattern in &case.patterns {
+                        self.resolve_pattern(pattern);
+                    }
+
+                    // Every name bound by one of the alternatives is visible in the case.
+                    self.begin_scope();
+                    let mut names = HashSet::new();
+                    for name in case.patterns.iter().flat_map(|pattern| pattern.bindings()) {
+                        if names.insert(&name.lexeme) {
+                            self.declare(name);
+                            self.define(name);
+                        }
+                    }
+                    if let Some(guard) = &case.guard {
+                        self.resolve_expression(guard);
+                    }
+                    self.resolve_statement(&case.body);
+                    self.end_scope();
+                }
+            },
+            Stmt::While(_keyword, condition, body) => {
                 self.resolve_expression(condition);
                 self.resolve_statement(body);
             }
-            Stmt::Class(name, superclass, methods) => {
+            Stmt::ForIn(name, iterable, body) => {
+                self.resolve_expression(iterable);
+                self.begin_scope();
+                self.declare(name);
+                self.define(name);
+                self.resolve_statement(body);
+                self.end_scope();
+            },
+            Stmt::Throw(_keyword, value) => self.resolve_expression(value),
+            Stmt::Yield(_keyword, value) => {
+                if self.current_function == FunctionType::None {
+                    panic!("Can't yield from top-level code.");
+                }
+                if self.current_function == FunctionType::Initializer {
+                    panic!("Can't yield from an initializer.");
+                }
+                self.resolve_expression(value);
+            },
+            Stmt::Import(_path, _names) => {
+                if !self.scope.is_empty() {
+                    panic!("Can only import at top-level.");
+                }
+            },
+            Stmt::Export(declaration) => {
+                if !self.scope.is_empty() {
+                    panic!("Can only export top-level declarations.");
+                }
+                self.resolve_statement(declaration);
+            },
+            Stmt::Try(body, catch_clause, finally_clause) => {
+                self.begin_scope();
+                self.resolve_statements(body);
+                self.end_scope();
+
+                if let Some((name, catch_body)) = catch_clause.as_ref() {
+                    self.begin_scope();
+                    self.declare(name);
+                    self.define(name);
+                    self.resolve_statements(catch_body);
+                    self.end_scope();
+                }
+
+                if let Some(finally_body) = finally_clause.as_ref() {
+                    self.begin_scope();
+                    self.resolve_statements(finally_body);
+                    self.end_scope();
+                }
+            },
+            Stmt::Enum(name, _members) => {
+                self.declare(name);
+                self.define(name);
+            },
+            Stmt::Trait(name, methods, getters, setters) => {
+                let enclosing_class = self.current_class;
+                self.current_class = ClassType::Trait;
+
+                self.declare(name);
+                self.define(name);
+
+                self.begin_scope();
+                self.scope.last_mut().unwrap().insert(String::from("this"), true);
+
+                for method in methods {
+                    if let Stmt::Function(name, _, _, _, _) = method {
+                        if name.lexeme == "init" {
+                            panic!("A trait can't have an initializer.");
+                        }
+                    }
+                }
+
+                for member in methods.iter().chain(getters.iter()).chain(setters.iter()) {
+                    if let Stmt::Function(_, parameters, body, _generator, _) = member {
+                        self.resolve_function(parameters, body, FunctionType::Method);
+                    } else {
+                        panic!();
+                    }
+                }
+
+                self.end_scope();
+                self.current_class = enclosing_class;
+            },
+            Stmt::Class(name, superclass, traits, methods, getters, setters, fields, abstracts) => {
                 let enclosng_class = self.current_class;
                 self.current_class = ClassType::Class;
 
                 self.declare(name);
                 self.define(name);
 
+                for used in traits {
+                    self.resolve_expression(used);
+                }
+
                 if let Some(superclass) = superclass.as_ref() {
                     if let ExprType::Variable(variable) = &superclass.expr_type {
                         self.begin_scope();
@@ -176,7 +374,7 @@ impl Resolver {
 
                 for method in methods {
                     let mut declaration = FunctionType::Method;
-                    if let Stmt::Function(name, parameters, body) = method {
+                    if let Stmt::Function(name, parameters, body, _generator, _) = method {
                         if name.lexeme == "init" {
                             declaration = FunctionType::Initializer;
                         }
@@ -186,6 +384,21 @@ impl Resolver {
                     }
                 }
 
+                // Field defaults are evaluated with `this` bound to the new instance.
+                for field in fields {
+                    if let Stmt::Var(_name, initializer, _) = field {
+                        self.resolve_expression(initializer);
+                    }
+                }
+
+                for accessor in getters.iter().chain(setters.iter()).chain(abstracts.iter()) {
+                    if let Stmt::Function(_, parameters, body, _generator, _) = accessor {
+                        self.resolve_function(parameters, body, FunctionType::Method);
+                    } else {
+                        panic!();
+                    }
+                }
+
                 self.end_scope();
 
                 if let Some(superclass) = superclass.as_ref() {
@@ -214,8 +427,24 @@ impl Resolver {
             },
             ExprType::Assign(name, value) => {
                 self.visit_expr(&value);
+                self.check_assignable(name);
                 self.resolve_local(expr, &name)
             },
+            ExprType::CompoundAssign(name, _op, value, _postfix) => {
+                self.visit_expr(value);
+                self.check_assignable(name);
+                self.resolve_local(expr, name)
+            },
+            ExprType::CompoundSet(object, name, _op, value, _postfix) => {
+                self.check_private(object, name);
+                self.resolve_expression(value);
+                self.resolve_expression(object);
+            },
+            ExprType::CompoundSetIndex(object, _bracket, index, _op, value, _postfix) => {
+                self.resolve_expression(value);
+                self.resolve_expression(object);
+                self.resolve_expression(index);
+            },
             ExprType::Binary(left, _op, right) => {
                 self.resolve_expression(&left);
                 self.resolve_expression(&right);
@@ -227,21 +456,56 @@ impl Resolver {
                 }
             },
             ExprType::Grouping(expr) => self.resolve_expression(&expr),
+            ExprType::List(elements) => {
+                for element in elements {
+                    self.resolve_expression(element);
+                }
+            },
             ExprType::Logical(left, _op, right) => {
                 self.resolve_expression(&left);
                 self.resolve_expression(&right);
             },
             ExprType::Unary(_op, expr) => self.resolve_expression(&expr),
-            ExprType::Get(expr, _name) => {
+            ExprType::Conditional(condition, then_branch, else_branch) => {
+                self.resolve_expression(condition);
+                self.resolve_expression(then_branch);
+                self.resolve_expression(else_branch);
+            },
+            ExprType::OptionalChain(expr) => self.resolve_expression(expr),
+            ExprType::OptionalGet(expr, name) => {
+                self.check_private(expr, name);
                 self.resolve_expression(expr);
             },
-            ExprType::Set(object, _name, value) => {
+            ExprType::Range(start, _operator, end, step) => {
+                self.resolve_expression(start);
+                self.resolve_expression(end);
+                if let Some(step) = step {
+                    self.resolve_expression(step);
+                }
+            },
+            ExprType::Get(expr, name) => {
+                self.check_private(expr, name);
+                self.resolve_expression(expr);
+            },
+            ExprType::Index(object, _bracket, index) => {
+                self.resolve_expression(object);
+                self.resolve_expression(index);
+            },
+            ExprType::SetIndex(object, _bracket, index, value) => {
+                self.resolve_expression(value);
+                self.resolve_expression(object);
+                self.resolve_expression(index);
+            },
+            ExprType::Set(object, name, value) => {
+                self.check_private(object, name);
                 self.resolve_expression(value);
                 self.resolve_expression(object);
             },
             ExprType::Super(keyword, _) => {
                 if self.current_class == ClassType::None {
                     panic!("Can't use 'super' outside of a class.");
+                } else if self.current_class == ClassType::Trait {
+                    panic!("Can't use 'super' in a trait.");
                 } else if self.current_class != ClassType::Subclass {
                     panic!("Can't use 'super' in a class with no superclass.");
                 }
@@ -39,12 +39,86 @@ impl Scanner {
             ')' => Some(self.consume(TokenType::RightParen)),
             '{' => Some(self.consume(TokenType::LeftBrace)),
             '}' => Some(self.consume(TokenType::RightBrace)),
+            '[' => Some(self.consume(TokenType::LeftBracket)),
+            ']' => Some(self.consume(TokenType::RightBracket)),
             ',' => Some(self.consume(TokenType::Comma)),
-            '.' => Some(self.consume(TokenType::Dot)),
-            '-' => Some(self.consume(TokenType::Minus)),
-            '+' => Some(self.consume(TokenType::Plus)),
+            '.' => {
+                if self.is_next('.') && self.source.get(1) == Some(&'.') {
+                    self.advance();
+                    Some(self.advance_and_consume(TokenType::DotDotDot))
+                } else if self.is_next('.') && self.source.get(1) == Some(&'=') {
+                    self.advance();
+                    Some(self.advance_and_consume(TokenType::DotDotEqual))
+                } else if self.is_next('.') {
+                    Some(self.advance_and_consume(TokenType::DotDot))
+                } else {
+                    Some(self.consume(TokenType::Dot))
+                }
+            },
+            '-' => {
+                if self.is_next('=') {
+                    Some(self.advance_and_consume(TokenType::MinusEqual))
+                } else if self.is_next('-') {
+                    Some(self.advance_and_consume(TokenType::MinusMinus))
+                } else {
+                    Some(self.consume(TokenType::Minus))
+                }
+            },
+            '+' => {
+                if self.is_next('=') {
+                    Some(self.advance_and_consume(TokenType::PlusEqual))
+                } else if self.is_next('+') {
+                    Some(self.advance_and_consume(TokenType::PlusPlus))
+                } else {
+                    Some(self.consume(TokenType::Plus))
+                }
+            },
             ';' => Some(self.consume(TokenType::Semicolon)),
-            '*' => Some(self.consume(TokenType::Star)),
+            ':' => Some(self.consume(TokenType::Colon)),
+            '?' => {
+                if self.is_next('?') {
+                    Some(self.advance_and_consume(TokenType::QuestionQuestion))
+                } else if self.is_next('.') {
+                    Some(self.advance_and_consume(TokenType::QuestionDot))
+                } else {
+                    Some(self.consume(TokenType::Question))
+                }
+            },
+            '*' => {
+                if self.is_next('*') {
+                    Some(self.advance_and_consume(TokenType::StarStar))
+                } else if self.is_next('=') {
+                    Some(self.advance_and_consume(TokenType::StarEqual))
+                } else {
+                    Some(self.consume(TokenType::Star))
+                }
+            },
+            '%' => {
+                if self.is_next('=') {
+                    Some(self.advance_and_consume(TokenType::PercentEqual))
+                } else {
+                    Some(self.consume(TokenType::Percent))
+                }
+            },
+            '#' => {
+                if !(self.peek().is_alphabetic() || self.peek() == '_') {
+                    return None;
+                }
+                while self.peek().is_alphanumeric() || self.peek() == '_' {
+                    self.advance();
+                }
+                Some(self.consume(TokenType::PrivateName))
+            },
+            '&' => Some(self.consume(TokenType::Ampersand)),
+            '|' => Some(self.consume(TokenType::Pipe)),
+            '^' => Some(self.consume(TokenType::Caret)),
+            '~' => {
+                if self.is_next('/') {
+                    Some(self.advance_and_consume(TokenType::TildeSlash))
+                } else {
+                    Some(self.consume(TokenType::Tilde))
+                }
+            },
             '!' => {
                 if self.is_next('=') {
                     Some(self.advance_and_consume(TokenType::BangEqual))
@@ -55,6 +129,8 @@ impl Scanner {
             '=' => {
                 if self.is_next('=') {
                     Some(self.advance_and_consume(TokenType::EqualEqual))
+                } else if self.is_next('>') {
+                    Some(self.advance_and_consume(TokenType::EqualGreater))
                 } else {
                     Some(self.consume(TokenType::Equal))
                 }
@@ -62,6 +138,8 @@ impl Scanner {
             '<' => {
                 if self.is_next('=') {
                     Some(self.advance_and_consume(TokenType::LessEqual))
+                } else if self.is_next('<') {
+                    Some(self.advance_and_consume(TokenType::LessLess))
                 } else {
                     Some(self.consume(TokenType::Less))
                 }
@@ -69,6 +147,8 @@ impl Scanner {
             '>' => {
                 if self.is_next('=') {
                     Some(self.advance_and_consume(TokenType::GreaterEqual))
+                } else if self.is_next('>') {
+                    Some(self.advance_and_consume(TokenType::GreaterGreater))
                 } else {
                     Some(self.consume(TokenType::Greater))
                 }
@@ -80,6 +160,8 @@ impl Scanner {
                     }
                     self.drop_lexeme();
                     None
+                } else if self.is_next('=') {
+                    Some(self.advance_and_consume(TokenType::SlashEqual))
                 } else {
                     Some(self.consume(TokenType::Slash))
                 }
@@ -107,8 +189,8 @@ impl Scanner {
     
                     Some(self.consume(TokenType::Number))
                 // identifier & keywords
-                } else if c.is_alphabetic() {
-                    while self.peek().is_alphanumeric() {
+                } else if c.is_alphabetic() || c == '_' {
+                    while self.peek().is_alphanumeric() || self.peek() == '_' {
                         self.advance();
                     }
     
@@ -218,6 +300,24 @@ fn map_keyword(keyword: &str) -> TokenType {
         "true" => TokenType::True,
         "var" => TokenType::Var,
         "while" => TokenType::While,
+        "throw" => TokenType::Throw,
+        "try" => TokenType::Try,
+        "catch" => TokenType::Catch,
+        "finally" => TokenType::Finally,
+        "yield" => TokenType::Yield,
+        "trait" => TokenType::Trait,
+        "with" => TokenType::With,
+        "typeof" => TokenType::TypeOf,
+        "is" => TokenType::Is,
+        "const" => TokenType::Const,
+        "match" => TokenType::Match,
+        "case" => TokenType::Case,
+        "enum" => TokenType::Enum,
+        "abstract" => TokenType::Abstract,
+        "import" => TokenType::Import,
+        "export" => TokenType::Export,
+        "from" => TokenType::From,
+        "in" => TokenType::In,
         _ => TokenType::Identifier
     }
 }
\ No newline at end of file
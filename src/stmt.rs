@@ -2,15 +2,90 @@ use std::rc::Rc;
 
 use crate::{token::Token, expr::Expr};
 
+/// A type written after a name, `name: Type`, or after a parameter list.
+/// A trailing `?` also allows nil. Only `check` mode looks at annotations.
+#[derive(Clone)]
+pub struct TypeAnnotation {
+    pub name: Token,
+    pub nullable: bool,
+}
+
+/// A function parameter with an optional default value. A rest parameter
+/// collects the remaining arguments into a list.
+#[derive(Clone)]
+pub struct Parameter {
+    pub name: Token,
+    pub default: Option<Expr>,
+    pub rest: bool,
+    pub annotation: Option<TypeAnnotation>,
+}
+
+/// Names bound by a destructuring declaration: list elements by position,
+/// optionally with the remaining ones, or map entries and fields by name.
+#[derive(Clone)]
+pub enum Destructure {
+    List(Vec<Token>, Option<Token>),
+    Fields(Vec<Token>),
+}
+
+/// A pattern of a `match` case. Bare names bind the matched value, `_`
+/// matches anything and a class pattern binds instances of the class.
+#[derive(Clone)]
+pub enum Pattern {
+    Wildcard,
+    Binding(Token),
+    Value(Expr),
+    Class(Expr, Token),
+    List(Vec<Pattern>, Option<Token>),
+}
+
+impl Pattern {
+
+    /// The names a successful match of this pattern binds.
+    pub fn bindings(&self) -> Vec<&Token> {
+        match self {
+            Pattern::Wildcard | Pattern::Value(_) => vec![],
+            Pattern::Binding(name) | Pattern::Class(_, name) if name.lexeme == "_" => vec![],
+            Pattern::Binding(name) | Pattern::Class(_, name) => vec![name],
+            Pattern::List(elements, rest) => elements.iter()
+                .flat_map(|element| element.bindings())
+                .chain(rest.iter())
+                .collect()
+        }
+    }
+
+}
+
+/// A case of a `match` statement. It runs its body for the first of its
+/// patterns that matches and whose bindings satisfy the guard.
+#[derive(Clone)]
+pub struct Case {
+    pub patterns: Vec<Pattern>,
+    pub guard: Option<Expr>,
+    pub body: Stmt,
+}
+
 #[derive(Clone)]
 pub enum Stmt {
+    Assign(Vec<Expr>, Box<Token>, Vec<Expr>),
     Block(Vec<Stmt>),
-    Class(Box<Token>, Box<Option<Expr>>, Vec<Stmt>),
+    Class(Box<Token>, Box<Option<Expr>>, Vec<Expr>, Vec<Stmt>, Vec<Stmt>, Vec<Stmt>, Vec<Stmt>, Vec<Stmt>),
+    Const(Box<Token>, Box<Expr>, Option<TypeAnnotation>),
+    Export(Box<Stmt>),
+    Enum(Box<Token>, Vec<Token>),
     Expression(Box<Expr>),
-    Function(Box<Token>, Vec<Token>, Rc<Vec<Stmt>>),
+    ForIn(Box<Token>, Box<Expr>, Box<Stmt>),
+    Function(Box<Token>, Rc<Vec<Parameter>>, Rc<Vec<Stmt>>, bool, Option<TypeAnnotation>),
     If(Box<Expr>, Box<Stmt>, Box<Option<Stmt>>),
-    Print(Box<Expr>),
-    Return(Box<Option<Expr>>),
-    Var(Box<Token>, Box<Expr>),
-    While(Box<Expr>, Box<Stmt>)
+    Import(Box<Token>, Option<Vec<Token>>),
+    Match(Box<Token>, Box<Expr>, Vec<Case>),
+    Print(Box<Token>, Box<Expr>),
+    Return(Box<Token>, Box<Option<Expr>>),
+    Throw(Box<Token>, Box<Expr>),
+    Trait(Box<Token>, Vec<Stmt>, Vec<Stmt>, Vec<Stmt>),
+    Try(Vec<Stmt>, Box<Option<(Token, Vec<Stmt>)>>, Box<Option<Vec<Stmt>>>),
+    Var(Box<Token>, Box<Expr>, Option<TypeAnnotation>),
+    VarDestructure(Box<Destructure>, Box<Expr>),
+    While(Box<Token>, Box<Expr>, Box<Stmt>),
+    Yield(Box<Token>, Box<Expr>)
 }
\ No newline at end of file
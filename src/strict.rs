@@ -0,0 +1,307 @@
+use std::{collections::{HashMap, HashSet}, fs::read_to_string, path::PathBuf};
+
+use crate::{error::report, expr::{Expr, ExprType}, function::{arity_error, max_arity, min_arity}, interpreter::PRELUDE, module::{exported, resolve_path},
+    native::NativeFunction, parser::parse, scanner::scan_tokens, stmt::{Destructure, Pattern, Stmt}, token::Token};
+
+/// Strict mode: reports references to globals that are never declared and calls
+/// to known functions and classes with the wrong number of arguments, before the
+/// script runs. Variables the resolver didn't resolve to a local are globals.
+/// Returns the number of reported errors.
+pub fn analyze(statements: &[Stmt], locals: &HashMap<usize, usize>, script_path: Option<&PathBuf>, search_path: &[PathBuf]) -> usize {
+    let mut analyzer = Analyzer::new(locals);
+    for native in NativeFunction::ALL {
+        analyzer.declare(&native.to_string(), Some(Callable::Function(native.arity(), Some(native.arity()))));
+    }
+    analyzer.declare_all(&parse(scan_tokens(PRELUDE.to_string())), None, search_path);
+    analyzer.seen.clear();
+    analyzer.declare_all(statements, script_path, search_path);
+
+    for statement in statements {
+        analyzer.visit_stmt(statement);
+    }
+    analyzer.errors
+}
+
+/// The arguments a global function or class can be called with.
+#[derive(Clone)]
+enum Callable {
+    Function(usize, Option<usize>),
+    // The arity of `init`, if declared, and the name of the superclass.
+    Class(Option<(usize, Option<usize>)>, Option<String>),
+}
+
+struct Analyzer<'a> {
+    locals: &'a HashMap<usize, usize>,
+    globals: HashSet<String>,
+    callables: HashMap<String, Callable>,
+    // Globals declared by the program being analyzed, as opposed to the
+    // natives and the prelude it may shadow.
+    seen: HashSet<String>,
+    // Set when a module whose exports are unknown is imported as a whole.
+    unknown_imports: bool,
+    errors: usize,
+}
+
+impl<'a> Analyzer<'a> {
+
+    fn new(locals: &'a HashMap<usize, usize>) -> Self {
+        Analyzer {
+            locals,
+            globals: HashSet::new(),
+            callables: HashMap::new(),
+            seen: HashSet::new(),
+            unknown_imports: false,
+            errors: 0,
+        }
+    }
+
+    fn error(&mut self, token: &Token, message: String) {
+        report(token.line, format!("at {}", token.lexeme), message);
+        self.errors += 1;
+    }
+
+    /// Declares a global. Only names declared once by a function or class
+    /// declaration keep a known arity.
+    fn declare(&mut self, name: &String, callable: Option<Callable>) {
+        let redeclared = !self.seen.insert(name.clone());
+        self.globals.insert(name.clone());
+        match callable {
+            Some(callable) if !redeclared => self.callables.insert(name.clone(), callable),
+            _ => self.callables.remove(name)
+        };
+    }
+
+    fn declare_all(&mut self, statements: &[Stmt], script_path: Option<&PathBuf>, search_path: &[PathBuf]) {
+        for statement in statements {
+            let statement = match statement {
+                Stmt::Export(declaration) => declaration.as_ref(),
+                statement => statement
+            };
+            match statement {
+                Stmt::Var(name, _, _) | Stmt::Const(name, _, _) | Stmt::Trait(name, _, _, _) | Stmt::Enum(name, _) => {
+                    self.declare(&name.lexeme, None);
+                },
+                Stmt::VarDestructure(destructure, _) => {
+                    let names = match destructure.as_ref() {
+                        Destructure::List(names, rest) => names.iter().chain(rest.iter()).collect::<Vec<&Token>>(),
+                        Destructure::Fields(names) => names.iter().collect()
+                    };
+                    for name in names {
+                        self.declare(&name.lexeme, None);
+                    }
+                },
+                Stmt::Function(name, parameters, _, _, _) => {
+                    self.declare(&name.lexeme, Some(Callable::Function(min_arity(parameters), max_arity(parameters))));
+                },
+                Stmt::Class(name, superclass, _, methods, _, _, _, _) => {
+                    let init = methods.iter().find_map(|method| match method {
+                        Stmt::Function(method, parameters, _, _, _) if method.lexeme == "init" => Some((min_arity(parameters), max_arity(parameters))),
+                        _ => None
+                    });
+                    let superclass = match superclass.as_ref() {
+                        Some(Expr { expr_type: ExprType::Variable(superclass), .. }) => Some(superclass.lexeme.clone()),
+                        _ => None
+                    };
+                    self.declare(&name.lexeme, Some(Callable::Class(init, superclass)));
+                },
+                Stmt::Import(_, Some(names)) => {
+                    for name in names {
+                        self.declare(&name.lexeme, None);
+                    }
+                },
+                Stmt::Import(path, None) => self.declare_module(path, script_path, search_path),
+                _ => {}
+            }
+        }
+    }
+
+    /// Declares the exports of a module imported as a whole.
+    fn declare_module(&mut self, path: &Token, script_path: Option<&PathBuf>, search_path: &[PathBuf]) {
+        let source = resolve_path(&path.lexeme, script_path, search_path)
+            .and_then(|module_path| read_to_string(module_path).ok());
+        match source {
+            Some(source) => {
+                for statement in parse(scan_tokens(source)).iter() {
+                    if let Some((name, _)) = exported(statement) {
+                        self.declare(name, None);
+                    }
+                }
+            },
+            None => self.unknown_imports = true
+        }
+    }
+
+    /// The arity of calling a global function or class, if it is known.
+    fn arity(&self, name: &str) -> Option<(usize, Option<usize>)> {
+        let mut callable = self.callables.get(name)?;
+        // Classes without `init` inherit it from their superclass.
+        for _ in 0..self.callables.len() {
+            match callable {
+                Callable::Function(min, max) => return Some((*min, *max)),
+                Callable::Class(Some(init), _) => return Some(*init),
+                Callable::Class(None, None) => return Some((0, Some(0))),
+                Callable::Class(None, Some(superclass)) => callable = self.callables.get(superclass)?
+            }
+        }
+        None
+    }
+
+    fn is_global(&self, expr: &Expr) -> bool {
+        !self.locals.contains_key(&expr.id)
+    }
+
+    fn check_defined(&mut self, expr: &Expr, name: &Token) {
+        if self.is_global(expr) && !self.unknown_imports && !self.globals.contains(&name.lexeme) {
+            self.error(name, format!("Undefined variable '{}'.", name.lexeme));
+        }
+    }
+
+    fn visit_statements(&mut self, statements: &[Stmt]) {
+        for statement in statements {
+            self.visit_stmt(statement);
+        }
+    }
+
+    fn visit_pattern(&mut self, pattern: &Pattern) {
+        match pattern {
+            Pattern::Wildcard | Pattern::Binding(_) => {},
+            Pattern::Value(value) | Pattern::Class(value, _) => self.visit_expr(value),
+            Pattern::List(elements, _) => {
+                for element in elements {
+                    self.visit_pattern(element);
+                }
+            }
+        }
+    }
+
+    fn visit_stmt(&mut self, stmt: &Stmt) {
+        match stmt {
+            Stmt::Assign(targets, _, values) => {
+                for expr in targets.iter().chain(values) {
+                    self.visit_expr(expr);
+                }
+            },
+            Stmt::Block(statements) => self.visit_statements(statements),
+            Stmt::Class(_, superclass, traits, methods, getters, setters, fields, abstracts) => {
+                for expr in superclass.iter().chain(traits) {
+                    self.visit_expr(expr);
+                }
+                for member in fields.iter().chain(methods).chain(getters).chain(setters).chain(abstracts) {
+                    self.visit_stmt(member);
+                }
+            },
+            Stmt::Const(_, initializer, _) | Stmt::Var(_, initializer, _) | Stmt::VarDestructure(_, initializer) => self.visit_expr(initializer),
+            Stmt::Enum(_, _) | Stmt::Import(_, _) => {},
+            Stmt::Export(declaration) => self.visit_stmt(declaration),
+            Stmt::Expression(expr) | Stmt::Print(_, expr) | Stmt::Throw(_, expr) | Stmt::Yield(_, expr) => self.visit_expr(expr),
+            Stmt::ForIn(_, iterable, body) => {
+                self.visit_expr(iterable);
+                self.visit_stmt(body);
+            },
+            Stmt::Function(_, parameters, body, _, _) => {
+                for default in parameters.iter().filter_map(|parameter| parameter.default.as_ref()) {
+                    self.visit_expr(default);
+                }
+                self.visit_statements(body);
+            },
+            Stmt::If(condition, then_branch, else_branch) => {
+                self.visit_expr(condition);
+                self.visit_stmt(then_branch);
+                if let Some(else_branch) = else_branch.as_ref() {
+                    self.visit_stmt(else_branch);
+                }
+            },
+            Stmt::Match(_, subject, cases) => {
+                self.visit_expr(subject);
+                for case in cases {
+                    for pattern in &case.patterns {
+                        self.visit_pattern(pattern);
+                    }
+                    if let Some(guard) = &case.guard {
+                        self.visit_expr(guard);
+                    }
+                    self.visit_stmt(&case.body);
+                }
+            },
+            Stmt::Return(_, value) => {
+                if let Some(value) = value.as_ref() {
+                    self.visit_expr(value);
+                }
+            },
+            Stmt::Trait(_, methods, getters, setters) => {
+                for member in methods.iter().chain(getters).chain(setters) {
+                    self.visit_stmt(member);
+                }
+            },
+            Stmt::Try(body, catch, finally) => {
+                self.visit_statements(body);
+                if let Some((_, catch)) = catch.as_ref() {
+                    self.visit_statements(catch);
+                }
+                if let Some(finally) = finally.as_ref() {
+                    self.visit_statements(finally);
+                }
+            },
+            Stmt::While(_keyword, condition, body) => {
+                self.visit_expr(condition);
+                self.visit_stmt(body);
+            },
+        }
+    }
+
+    fn visit_expr(&mut self, expr: &Expr) {
+        match &expr.expr_type {
+            ExprType::Assign(name, value) | ExprType::CompoundAssign(name, _, value, _) => {
+                self.check_defined(expr, name);
+                self.visit_expr(value);
+            },
+            ExprType::Binary(left, _, right) | ExprType::Logical(left, _, right) | ExprType::Index(left, _, right) => {
+                self.visit_expr(left);
+                self.visit_expr(right);
+            },
+            ExprType::Call(callee, paren, arguments) => {
+                if let ExprType::Variable(name) = &callee.expr_type {
+                    let arity = if self.is_global(callee) { self.arity(&name.lexeme) } else { None };
+                    if let Some(message) = arity.and_then(|(min, max)| arity_error(min, max, arguments.len())) {
+                        self.error(paren, message);
+                    }
+                }
+                self.visit_expr(callee);
+                for argument in arguments {
+                    self.visit_expr(argument);
+                }
+            },
+            ExprType::CompoundSet(object, _, _, value, _) | ExprType::Set(object, _, value) => {
+                self.visit_expr(object);
+                self.visit_expr(value);
+            },
+            ExprType::CompoundSetIndex(object, _, index, _, value, _) | ExprType::SetIndex(object, _, index, value) => {
+                self.visit_expr(object);
+                self.visit_expr(index);
+                self.visit_expr(value);
+            },
+            ExprType::Conditional(condition, then_branch, else_branch) => {
+                self.visit_expr(condition);
+                self.visit_expr(then_branch);
+                self.visit_expr(else_branch);
+            },
+            ExprType::Get(object, _) | ExprType::OptionalGet(object, _) => self.visit_expr(object),
+            ExprType::Grouping(expr) | ExprType::OptionalChain(expr) | ExprType::Unary(_, expr) => self.visit_expr(expr),
+            ExprType::List(elements) => {
+                for element in elements {
+                    self.visit_expr(element);
+                }
+            },
+            ExprType::Literal(_) | ExprType::Super(_, _) | ExprType::This(_) => {},
+            ExprType::Range(start, _, end, step) => {
+                self.visit_expr(start);
+                self.visit_expr(end);
+                if let Some(step) = step {
+                    self.visit_expr(step);
+                }
+            },
+            ExprType::Variable(name) => self.check_defined(expr, name),
+        }
+    }
+
+}
@@ -20,6 +20,12 @@ impl Token {
     }
 }
 
+/// A member name as written in the source. The parser qualifies private names
+/// with the id of the declaration they appear in, which is left out here.
+pub fn source_name(name: &str) -> &str {
+    name.find('#').map_or(name, |start| &name[start..])
+}
+
 impl fmt::Display for Token {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "{}  {} {}", self.token_type, self.lexeme, self.literal)
@@ -30,21 +36,30 @@ impl fmt::Display for Token {
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum TokenType {
     // Single-character tokens.
-    LeftParen, RightParen, LeftBrace, RightBrace,
+    LeftParen, RightParen, LeftBrace, RightBrace, LeftBracket, RightBracket,
     Comma, Dot, Minus, Plus, Semicolon, Slash, Star,
+    Percent, Ampersand, Pipe, Caret,
 
     // One or two character tokens.
     Bang, BangEqual,
     Equal, EqualEqual,
-    Greater, GreaterEqual,
-    Less, LessEqual,
+    Greater, GreaterEqual, GreaterGreater,
+    Less, LessEqual, LessLess,
+    StarStar, Tilde, TildeSlash,
+    PlusEqual, MinusEqual, StarEqual, SlashEqual, PercentEqual,
+    PlusPlus, MinusMinus,
+    Question, QuestionQuestion, QuestionDot, Colon, DotDot, DotDotEqual, DotDotDot,
+    EqualGreater,
 
     // Literals.
-    Identifier, String, Number,
+    Identifier, PrivateName, String, Number,
 
     // Keywords.
     And, Class, Else, False, Fun, For, If, Nil, Or,
     Print, Return, Super, This, True, Var, While,
+    Throw, Try, Catch, Finally,
+    Yield, Trait, With, TypeOf, Is, Const, Match, Case, Enum, Abstract,
+    Import, Export, From, In,
 
     Eof
 }
@@ -0,0 +1,59 @@
+mod common;
+
+use common::lox;
+
+#[test]
+fn operators_bind_by_precedence() {
+    let (stdout, stderr, success) = lox(&[], r#"
+        print 2 + 3 * 4 ** 2;
+        print 2 ** 3 ** 2;
+        print -2 ** 2;
+        print 1 + 7 % 4 * 2;
+        print 1 << 2 + 1;
+        print 1 | 2 ^ 3 & 1;
+        print 6 & 3 == 2;
+    "#);
+    assert!(success, "{}", stderr);
+    assert_eq!(stdout, "50\n512\n-4\n7\n8\n3\ntrue\n");
+}
+
+#[test]
+fn floor_division_and_modulo_round_towards_negative_infinity() {
+    let (stdout, stderr, success) = lox(&[], r#"
+        print 7 ~/ 2;
+        print -7 ~/ 2;
+        print 7 ~/ -2;
+        print 7.5 ~/ 2;
+        print -7 % 3;
+        print 7 % -3;
+    "#);
+    assert!(success, "{}", stderr);
+    assert_eq!(stdout, "3\n-4\n-4\n3\n2\n-2\n");
+}
+
+#[test]
+fn division_by_zero_follows_floating_point() {
+    let (stdout, stderr, success) = lox(&[], r#"
+        print 7 / 0;
+        print 7 ~/ 0;
+        print -7 ~/ 0;
+        print 0 ~/ 0;
+        print 7 % 0;
+    "#);
+    assert!(success, "{}", stderr);
+    assert_eq!(stdout, "inf\ninf\n-inf\nNaN\nNaN\n");
+}
+
+#[test]
+fn bitwise_operators_reject_fractions_and_bad_shifts() {
+    let (stdout, stderr, success) = lox(&[], r#"
+        print ~5;
+        print -8 >> 1;
+        try { print 1 & 2.5; } catch (e) { print e.message; }
+        try { print ~0.5; } catch (e) { print e.message; }
+        try { print 1 << 64; } catch (e) { print e.message; }
+        try { print 1 << -1; } catch (e) { print e.message; }
+    "#);
+    assert!(success, "{}", stderr);
+    assert_eq!(stdout, "-6\n-4\nOperands must be integers.\nOperand must be an integer.\nShift amount must be between 0 and 63.\nShift amount must be between 0 and 63.\n");
+}
@@ -0,0 +1,74 @@
+mod common;
+
+use common::lox;
+
+#[test]
+fn compound_assignment_to_properties_evaluates_the_object_once() {
+    let (stdout, stderr, success) = lox(&[], r#"
+        class Counter { init() { this.n = 1; } }
+        var counter = Counter();
+        var lookups = 0;
+        fun target() {
+            lookups++;
+            return counter;
+        }
+        target().n += 4;
+        target().n *= 2;
+        print target().n++;
+        print ++target().n;
+        print counter.n;
+        print lookups;
+    "#);
+    assert!(success, "{}", stderr);
+    assert_eq!(stdout, "10\n12\n12\n4\n");
+}
+
+#[test]
+fn compound_assignment_to_indexes_evaluates_the_index_once() {
+    let (stdout, stderr, success) = lox(&[], r#"
+        var list = [1, 2, 3];
+        var lookups = 0;
+        fun second() {
+            lookups++;
+            return 1;
+        }
+        list[second()] -= 5;
+        print list[second()]--;
+        print --list[second()];
+        print list;
+        print lookups;
+        var counts = Map();
+        counts["a"] = 1;
+        counts["a"] += 1;
+        print counts["a"];
+    "#);
+    assert!(success, "{}", stderr);
+    assert_eq!(stdout, "-3\n-5\n[1, -5, 3]\n3\n2\n");
+}
+
+#[test]
+fn increments_return_the_old_or_new_value() {
+    let (stdout, stderr, success) = lox(&[], r#"
+        var n = 5;
+        print n++;
+        print n;
+        print --n;
+        var text = "a";
+        text += "b";
+        print text;
+    "#);
+    assert!(success, "{}", stderr);
+    assert_eq!(stdout, "5\n6\n5\nab\n");
+}
+
+#[test]
+fn compound_assignment_checks_operand_types() {
+    let (stdout, stderr, success) = lox(&[], r#"
+        var missing;
+        print "before";
+        missing += 1;
+    "#);
+    assert!(!success);
+    assert_eq!(stdout, "before\n");
+    assert!(stderr.contains("[line 4] Uncaught Error: Operands must be two numbers or two strings."), "{}", stderr);
+}
@@ -0,0 +1,39 @@
+mod common;
+
+use common::lox;
+
+#[test]
+fn reports_mismatched_annotations_without_running() {
+    let (stdout, stderr, success) = lox(&["check"], r#"
+        print "not run";
+        var n: number = "one";
+        fun add(a: number, b: number): number { return a + b; }
+        add(1, "two");
+    "#);
+    assert!(!success);
+    assert_eq!(stdout, "");
+    assert!(stderr.contains("Can't assign string to 'n' of type number."), "{}", stderr);
+    assert!(stderr.contains("Argument 2 of 'add' must be number, got string."), "{}", stderr);
+}
+
+#[test]
+fn unannotated_variables_accept_any_later_value() {
+    let (_, stderr, success) = lox(&["check"], r#"
+        var status = 0;
+        status = "done";
+        var list = [1, 2];
+        list = nil;
+    "#);
+    assert!(success, "{}", stderr);
+    assert_eq!(stderr, "");
+}
+
+#[test]
+fn constants_keep_their_inferred_type() {
+    let (_, stderr, success) = lox(&["check"], r#"
+        const count = 1;
+        var name: string = count;
+    "#);
+    assert!(!success);
+    assert!(stderr.contains("Can't assign number to 'name' of type string."), "{}", stderr);
+}
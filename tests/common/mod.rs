@@ -0,0 +1,28 @@
+use std::{env, fs, process::Command, sync::atomic::{AtomicUsize, Ordering}};
+
+/// Runs `source` as a script with the interpreter, passing `args` before the
+/// script path, and returns its stdout, its stderr and whether it succeeded.
+pub fn lox(args: &[&str], source: &str) -> (String, String, bool) {
+    lox_files(args, &[("main.lox", source)])
+}
+
+/// Like `lox`, for scripts made of several files. The files are written to a
+/// fresh directory, at their relative paths, and the first one is run.
+pub fn lox_files(args: &[&str], files: &[(&str, &str)]) -> (String, String, bool) {
+    static SCRIPTS: AtomicUsize = AtomicUsize::new(0);
+    let directory = env::temp_dir().join(format!("lox-r-test-{}-{}", std::process::id(), SCRIPTS.fetch_add(1, Ordering::SeqCst)));
+    for (path, source) in files {
+        let path = directory.join(path);
+        fs::create_dir_all(path.parent().unwrap()).expect("Could not create directory.");
+        fs::write(&path, source).expect("Could not write script.");
+    }
+
+    let output = Command::new(env!("CARGO_BIN_EXE_lox-r"))
+        .args(args)
+        .arg(directory.join(files[0].0))
+        .output()
+        .expect("Could not run lox-r.");
+    fs::remove_dir_all(&directory).expect("Could not remove scripts.");
+
+    (String::from_utf8_lossy(&output.stdout).into_owned(), String::from_utf8_lossy(&output.stderr).into_owned(), output.status.success())
+}
@@ -0,0 +1,66 @@
+mod common;
+
+use common::lox;
+
+#[test]
+fn optional_chaining_on_nil_short_circuits_the_whole_chain() {
+    let (stdout, stderr, success) = lox(&[], r#"
+        class Node {
+            init(value, next) {
+                this.value = value;
+                this.next = next;
+            }
+            describe() { return "node " + this.value; }
+        }
+        var list = Node(1, nil);
+        print list?.describe();
+        print list?.next?.value;
+        print list?.next?.describe();
+        var none = nil;
+        print none?.a.b.c;
+        print none?.method(undefinedFunction());
+    "#);
+    assert!(success, "{}", stderr);
+    assert_eq!(stdout, "node 1\nnil\nnil\nnil\nnil\n");
+}
+
+#[test]
+fn plain_property_access_on_nil_still_fails() {
+    let (stdout, stderr, success) = lox(&[], r#"
+        var none = nil;
+        print none?.value;
+        print none.value;
+    "#);
+    assert!(!success);
+    assert_eq!(stdout, "nil\n");
+    assert!(stderr.contains("[line 4] Uncaught Error: Only instances have properties."), "{}", stderr);
+}
+
+#[test]
+fn null_coalescing_only_replaces_nil() {
+    let (stdout, stderr, success) = lox(&[], r#"
+        var evaluated = 0;
+        fun fallback() {
+            evaluated++;
+            return "fallback";
+        }
+        print nil ?? fallback();
+        print false ?? fallback();
+        print 0 ?? fallback();
+        print evaluated;
+    "#);
+    assert!(success, "{}", stderr);
+    assert_eq!(stdout, "fallback\nfalse\n0\n1\n");
+}
+
+#[test]
+fn conditional_expressions_nest_to_the_right() {
+    let (stdout, stderr, success) = lox(&[], r#"
+        fun sign(n) { return n > 0 ? "positive" : n < 0 ? "negative" : "zero"; }
+        print sign(3);
+        print sign(-3);
+        print sign(0);
+    "#);
+    assert!(success, "{}", stderr);
+    assert_eq!(stdout, "positive\nnegative\nzero\n");
+}
@@ -0,0 +1,43 @@
+mod common;
+
+use common::lox;
+
+#[test]
+fn assigning_to_constant_fails_before_running() {
+    let (stdout, stderr, success) = lox(&[], r#"
+        const limit = 4;
+        print "started";
+        limit = 5;
+    "#);
+    assert!(!success);
+    assert_eq!(stdout, "");
+    assert!(stderr.contains("limit Can't assign to constant."), "{}", stderr);
+}
+
+#[test]
+fn compound_assignment_to_local_constant_fails() {
+    let (stdout, stderr, success) = lox(&[], r#"
+        fun step() {
+            const size = 1;
+            size++;
+        }
+    "#);
+    assert!(!success);
+    assert_eq!(stdout, "");
+    assert!(stderr.contains("size Can't assign to constant."), "{}", stderr);
+}
+
+#[test]
+fn constants_can_be_shadowed_in_inner_scopes() {
+    let (stdout, _, success) = lox(&[], r#"
+        const value = 1;
+        {
+            var value = 2;
+            value += 1;
+            print value;
+        }
+        print value;
+    "#);
+    assert!(success);
+    assert_eq!(stdout, "3\n1\n");
+}
@@ -0,0 +1,73 @@
+mod common;
+
+use common::lox;
+
+#[test]
+fn list_patterns_pad_with_nil_and_ignore_extra_elements() {
+    let (stdout, stderr, success) = lox(&[], r#"
+        var [a, b] = [1];
+        print a;
+        print b;
+        var [first] = [1, 2, 3];
+        print first;
+        var [head, ...tail] = [1, 2, 3];
+        print tail;
+        var [only, ...none] = [1];
+        print none;
+    "#);
+    assert!(success, "{}", stderr);
+    assert_eq!(stdout, "1\nnil\n1\n[2, 3]\n[]\n");
+}
+
+#[test]
+fn field_patterns_read_instances_and_maps() {
+    let (stdout, stderr, success) = lox(&[], r#"
+        class Person { init() { this.name = "ada"; } }
+        var {name, age} = Person();
+        print name;
+        print age;
+        var point = Map();
+        point["x"] = 1;
+        var {x, y} = point;
+        print x;
+        print y;
+    "#);
+    assert!(success, "{}", stderr);
+    assert_eq!(stdout, "ada\nnil\n1\nnil\n");
+}
+
+#[test]
+fn multiple_assignment_swaps_and_unpacks() {
+    let (stdout, stderr, success) = lox(&[], r#"
+        var a = 1;
+        var b = 2;
+        a, b = b, a;
+        print a;
+        print b;
+        a, b = [5];
+        print a;
+        print b;
+    "#);
+    assert!(success, "{}", stderr);
+    assert_eq!(stdout, "2\n1\n5\nnil\n");
+}
+
+#[test]
+fn mismatched_assignments_and_values_fail() {
+    let (stdout, stderr, success) = lox(&[], r#"
+        try { var [a, b] = 5; } catch (e) { print e.message; }
+        try { var {c} = "text"; } catch (e) { print e.message; }
+    "#);
+    assert!(success, "{}", stderr);
+    assert_eq!(stdout, "Only lists can be destructured with '[...]'.\nOnly maps and instances can be destructured with '{...}'.\n");
+
+    let (stdout, stderr, success) = lox(&[], r#"
+        var a;
+        var b;
+        print "not run";
+        a, b = 1, 2, 3;
+    "#);
+    assert!(!success);
+    assert_eq!(stdout, "");
+    assert!(stderr.contains("[line 5] Error at =: Expect 2 values but got 3."), "{}", stderr);
+}
@@ -0,0 +1,58 @@
+mod common;
+
+use common::lox;
+
+#[test]
+fn members_are_equal_only_to_themselves() {
+    let (stdout, stderr, success) = lox(&[], r#"
+        enum Color { Red, Green }
+        enum Light { Red, Green }
+        var red = Color.Red;
+        print red == Color.Red;
+        print red == Color.Green;
+        print red == Light.Red;
+        print red == "Red";
+        print red == 0;
+        fun make() {
+            enum Color { Red }
+            return Color.Red;
+        }
+        print make() == red;
+        print make() == make();
+    "#);
+    assert!(success, "{}", stderr);
+    assert_eq!(stdout, "true\nfalse\nfalse\nfalse\nfalse\nfalse\nfalse\n");
+}
+
+#[test]
+fn members_are_distinct_map_keys() {
+    let (stdout, stderr, success) = lox(&[], r#"
+        enum Color { Red }
+        enum Light { Red }
+        var names = Map();
+        names[Color.Red] = "color";
+        names[Light.Red] = "light";
+        print names.size;
+        print names[Color.Red];
+        print names[Light.Red];
+    "#);
+    assert!(success, "{}", stderr);
+    assert_eq!(stdout, "2\ncolor\nlight\n");
+}
+
+#[test]
+fn members_have_a_name_and_ordinal_and_compare_within_their_enum() {
+    let (stdout, stderr, success) = lox(&[], r#"
+        enum Color { Red, Green }
+        enum Light { Red }
+        print Color.Green;
+        print Color.Green.name;
+        print Color.Green.ordinal;
+        print Color.Red < Color.Green;
+        for (var color in Color) print color.name;
+        try { print Color.Red < Light.Red; } catch (e) { print e.message; }
+        try { print Color.Blue; } catch (e) { print e.message; }
+    "#);
+    assert!(success, "{}", stderr);
+    assert_eq!(stdout, "Color.Green\nGreen\n1\ntrue\nRed\nGreen\nCan only compare members of the same enum.\nEnum 'Color' has no member 'Blue'.\n");
+}
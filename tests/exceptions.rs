@@ -0,0 +1,43 @@
+mod common;
+
+use common::lox;
+
+#[test]
+fn catches_thrown_values_and_runtime_errors() {
+    let (stdout, _, success) = lox(&[], r#"
+        try { throw "boom"; } catch (e) { print "caught " + e; }
+        try { print 1 / nil; } catch (e) { print e.message; }
+    "#);
+    assert!(success);
+    assert_eq!(stdout, "caught boom\nOperands must be numbers.\n");
+}
+
+#[test]
+fn finally_runs_when_leaving_by_throw_or_return() {
+    let (stdout, _, success) = lox(&[], r#"
+        fun fails() {
+            try { throw "boom"; } finally { print "cleanup"; }
+        }
+        try { fails(); } catch (e) { print "caught " + e; }
+
+        fun returns() {
+            try { return "body"; } finally { print "finally"; }
+        }
+        print returns();
+    "#);
+    assert!(success);
+    assert_eq!(stdout, "cleanup\ncaught boom\nfinally\nbody\n");
+}
+
+#[test]
+fn uncaught_exception_reports_stack_trace() {
+    let (stdout, stderr, success) = lox(&[], r#"
+        fun inner() { throw "uncaught"; }
+        print "before";
+        inner();
+    "#);
+    assert!(!success);
+    assert_eq!(stdout, "before\n");
+    assert!(stderr.contains("Uncaught uncaught"), "{}", stderr);
+    assert!(stderr.contains("at inner()"), "{}", stderr);
+}
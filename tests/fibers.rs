@@ -0,0 +1,101 @@
+mod common;
+
+use common::lox;
+
+#[test]
+fn values_travel_both_ways_through_yield() {
+    let (stdout, _, success) = lox(&[], r#"
+        fun accumulator() {
+            var total = 0;
+            while (true) {
+                total += Fiber.yield(total);
+            }
+        }
+        var sum = Fiber(accumulator);
+        sum.call();
+        print sum.call(5);
+        print sum.call(10);
+    "#);
+    assert!(success);
+    assert_eq!(stdout, "5\n15\n");
+}
+
+#[test]
+fn yield_suspends_nested_calls() {
+    let (stdout, _, success) = lox(&[], r#"
+        fun wait() { Fiber.yield(); }
+        fun steps() {
+            print "one";
+            wait();
+            print "two";
+        }
+        var fiber = Fiber(steps);
+        fiber.call();
+        print fiber.isDone;
+        fiber.call();
+        print fiber.isDone;
+    "#);
+    assert!(success);
+    assert_eq!(stdout, "one\nfalse\ntwo\ntrue\n");
+}
+
+#[test]
+fn transfer_switches_between_fibers() {
+    let (stdout, _, success) = lox(&[], r#"
+        var ping;
+        var pong;
+        fun pinger() {
+            for (var i = 1; i <= 2; i++) {
+                print "ping " + i;
+                pong.transfer();
+            }
+            return "over";
+        }
+        fun ponger() {
+            while (true) {
+                print "pong";
+                ping.transfer();
+            }
+        }
+        ping = Fiber(pinger);
+        pong = Fiber(ponger);
+        print ping.transfer();
+    "#);
+    assert!(success);
+    assert_eq!(stdout, "ping 1\npong\nping 2\npong\nover\n");
+}
+
+#[test]
+fn errors_inside_fibers_are_thrown_to_the_resumer() {
+    let (stdout, stderr, success) = lox(&[], r#"
+        fun broken() {
+            Fiber.yield();
+            throw Error("crashed");
+        }
+        var fiber = Fiber(broken);
+        fiber.call();
+        try { fiber.call(); } catch (error) { print error.message; }
+        fiber.call();
+    "#);
+    assert!(!success);
+    assert_eq!(stdout, "crashed\n");
+    assert!(stderr.contains("Cannot resume a finished fiber."), "{}", stderr);
+}
+
+#[test]
+fn deep_recursion_inside_a_fiber_raises_an_error() {
+    let (stdout, stderr, success) = lox(&[], r#"
+        fun depth(n) {
+            if (n == 0) return Fiber.yield(0);
+            return depth(n - 1) + 1;
+        }
+        fun shallow() { return depth(10); }
+        fun deep() { return depth(500); }
+        var fiber = Fiber(shallow);
+        print fiber.call();
+        print fiber.call(5);
+        try { Fiber(deep).call(); } catch (error) { print error.message; }
+    "#);
+    assert!(success, "{}", stderr);
+    assert_eq!(stdout, "0\n15\nStack overflow.\n");
+}
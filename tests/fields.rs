@@ -0,0 +1,67 @@
+mod common;
+
+use common::lox;
+
+#[test]
+fn fields_are_initialized_before_init_runs() {
+    let (stdout, stderr, success) = lox(&[], r#"
+        class Counter {
+            var count = 0;
+            var items = [];
+            var label;
+            init() { print this.count; this.count = 2; }
+        }
+        var a = Counter();
+        var b = Counter();
+        a.items.push(1);
+        print a.count;
+        print b.items;
+        print a.label;
+        try { print a.missing; } catch (e) { print e.message; }
+    "#);
+    assert!(success, "{}", stderr);
+    assert_eq!(stdout, "0\n0\n2\n[]\nnil\nUndefined property 'missing'.\n");
+}
+
+#[test]
+fn abstract_methods_are_called_through_the_subclass() {
+    let (stdout, stderr, success) = lox(&[], r#"
+        class Shape {
+            var name;
+            abstract area();
+            describe() { return this.name + " " + this.area(); }
+        }
+        class Mid < Shape { abstract area(); }
+        class Square < Mid {
+            var side = 2;
+            init() { this.name = "square"; }
+            area() { return this.side * this.side; }
+        }
+        print Square().describe();
+    "#);
+    assert!(success, "{}", stderr);
+    assert_eq!(stdout, "square 4\n");
+}
+
+#[test]
+fn abstract_classes_cannot_be_instantiated() {
+    let (stdout, stderr, success) = lox(&[], r#"
+        class Shape { abstract area(); }
+        class Mid < Shape { abstract area(); }
+        try { Shape(); } catch (e) { print e.message; }
+        try { Mid(); } catch (e) { print e.message; }
+    "#);
+    assert!(success, "{}", stderr);
+    assert_eq!(stdout, "Can't instantiate abstract class 'Shape', it doesn't implement 'area'.\nCan't instantiate abstract class 'Mid', it doesn't implement 'area'.\n");
+}
+
+#[test]
+fn subclasses_must_implement_abstract_methods() {
+    let (stdout, stderr, success) = lox(&[], r#"
+        class Shape { abstract area(); }
+        class Square < Shape { other() {} }
+    "#);
+    assert!(!success);
+    assert_eq!(stdout, "");
+    assert!(stderr.contains("Class 'Square' must implement abstract method 'area' of 'Shape'."), "{}", stderr);
+}
@@ -0,0 +1,96 @@
+mod common;
+
+use common::lox;
+
+#[test]
+fn warns_about_unreachable_code_and_still_runs() {
+    let (stdout, stderr, success) = lox(&[], r#"
+        fun f() {
+            return 1;
+            print "never";
+        }
+        print f();
+    "#);
+    assert!(success);
+    assert_eq!(stdout, "1\n");
+    assert!(stderr.contains("Warning at return: Code after 'return' is unreachable."), "{}", stderr);
+}
+
+#[test]
+fn warns_about_missing_returns_and_endless_loops() {
+    let (_, stderr, _) = lox(&["check"], r#"
+        fun sign(n) {
+            if (n > 0) return 1;
+        }
+        fun spin() {
+            while (true) {}
+        }
+    "#);
+    assert!(stderr.contains("Function 'sign' returns a value on some paths but not on all."), "{}", stderr);
+    assert!(stderr.contains("Warning at while: Loop never exits."), "{}", stderr);
+}
+
+#[test]
+fn exhaustive_matches_return_on_all_paths() {
+    let (_, stderr, success) = lox(&["check"], r#"
+        enum Light { Red, Yellow, Green }
+        fun next(light) {
+            match (light) {
+                case Light.Red => return Light.Green;
+                case Light.Green => return Light.Yellow;
+                case Light.Yellow => return Light.Red;
+            }
+        }
+        fun describe(value) {
+            match (value) {
+                case 0 => return "zero";
+                case _ => return "other";
+            }
+        }
+    "#);
+    assert!(success, "{}", stderr);
+    assert_eq!(stderr, "");
+}
+
+#[test]
+fn match_missing_an_enum_member_may_fall_through() {
+    let (_, stderr, _) = lox(&["check"], r#"
+        enum Light { Red, Yellow, Green }
+        fun next(light) {
+            match (light) {
+                case Light.Red => return Light.Green;
+                case Light.Green => return Light.Yellow;
+            }
+        }
+    "#);
+    assert!(stderr.contains("Function 'next' returns a value on some paths but not on all."), "{}", stderr);
+}
+
+#[test]
+fn loops_with_operations_that_may_throw_can_exit() {
+    let (_, stderr, success) = lox(&["check"], r#"
+        fun add(a, b) {
+            var x;
+            while (true) { x = a + b; }
+        }
+        fun read(point) {
+            while (true) { print point.x; }
+        }
+        fun fill(list) {
+            while (true) { list[0] = 1; }
+        }
+    "#);
+    assert!(success, "{}", stderr);
+    assert_eq!(stderr, "");
+}
+
+#[test]
+fn loops_with_only_literal_operations_never_exit() {
+    let (_, stderr, _) = lox(&["check"], r#"
+        fun spin() {
+            var x;
+            while (true) { x = 1 + 2; }
+        }
+    "#);
+    assert!(stderr.contains("Warning at while: Loop never exits."), "{}", stderr);
+}
@@ -0,0 +1,73 @@
+mod common;
+
+use common::lox;
+
+#[test]
+fn generators_produce_values_lazily() {
+    let (stdout, _, success) = lox(&[], r#"
+        fun naturals() {
+            var n = 0;
+            while (true) {
+                print "computing " + n;
+                yield n;
+                n++;
+            }
+        }
+        var numbers = naturals();
+        print numbers.next();
+        print numbers.next();
+    "#);
+    assert!(success);
+    assert_eq!(stdout, "computing 0\n0\ncomputing 1\n1\n");
+}
+
+#[test]
+fn generators_compose_in_for_loops() {
+    let (stdout, _, success) = lox(&[], r#"
+        fun range(n) {
+            for (var i = 0; i < n; i++) yield i;
+        }
+        fun squares(sequence) {
+            for (var value in sequence) yield value * value;
+        }
+        var squared = [];
+        for (var square in squares(range(4))) squared.push(square);
+        print squared;
+    "#);
+    assert!(success);
+    assert_eq!(stdout, "[0, 1, 4, 9]\n");
+}
+
+#[test]
+fn errors_inside_generators_propagate_to_the_caller() {
+    let (stdout, _, success) = lox(&[], r#"
+        fun failing() {
+            yield 1;
+            throw "done badly";
+        }
+        var values = failing();
+        print values.next();
+        try { values.next(); } catch (e) { print "caught " + e; }
+    "#);
+    assert!(success);
+    assert_eq!(stdout, "1\ncaught done badly\n");
+}
+
+#[test]
+fn deep_recursion_inside_a_generator_raises_an_error() {
+    let (stdout, stderr, success) = lox(&[], r#"
+        fun depth(n) {
+            if (n == 0) return 0;
+            return depth(n - 1) + 1;
+        }
+        fun deep() {
+            yield depth(10);
+            yield depth(100000);
+        }
+        var values = deep();
+        print values.next();
+        try { values.next(); } catch (e) { print e.message; }
+    "#);
+    assert!(success, "{}", stderr);
+    assert_eq!(stdout, "10\nStack overflow.\n");
+}
@@ -0,0 +1,67 @@
+mod common;
+
+use common::lox;
+
+#[test]
+fn for_in_visits_lists_strings_maps_and_ranges() {
+    let (stdout, stderr, success) = lox(&[], r#"
+        for (var x in [1, 2]) print x;
+        for (var c in "hi") print c;
+        var ages = Map();
+        ages["ada"] = 36;
+        ages["alan"] = 41;
+        for (var name in ages) print name + " " + ages[name];
+        for (var i in 0..3) print i;
+        for (var i in 3..=0 by -2) print i;
+        for (var x in []) print "never";
+    "#);
+    assert!(success, "{}", stderr);
+    assert_eq!(stdout, "1\n2\nh\ni\nada 36\nalan 41\n0\n1\n2\n3\n1\n");
+}
+
+#[test]
+fn for_in_uses_the_iterator_protocol() {
+    let (stdout, stderr, success) = lox(&[], r#"
+        class Countdown {
+            init(n) { this.n = n; }
+            hasNext() { return this.n > 0; }
+            next() {
+                this.n--;
+                return this.n + 1;
+            }
+        }
+        class Launch {
+            iterator() { return Countdown(2); }
+        }
+        for (var x in Countdown(3)) print x;
+        for (var x in Launch()) print x;
+    "#);
+    assert!(success, "{}", stderr);
+    assert_eq!(stdout, "3\n2\n1\n2\n1\n");
+}
+
+#[test]
+fn each_iteration_has_its_own_variable() {
+    let (stdout, stderr, success) = lox(&[], r#"
+        var readers = [];
+        for (var i in 0..3) {
+            fun read() { return i; }
+            readers.push(read);
+        }
+        for (var read in readers) print read();
+    "#);
+    assert!(success, "{}", stderr);
+    assert_eq!(stdout, "0\n1\n2\n");
+}
+
+#[test]
+fn iterating_other_values_fails() {
+    let (stdout, stderr, success) = lox(&[], r#"
+        class Empty {}
+        try { for (var x in Empty()) print x; } catch (e) { print e.message; }
+        for (var x in 5) print x;
+    "#);
+    assert!(!success);
+    assert_eq!(stdout, "Empty instance is not iterable: define 'iterator()' or 'hasNext()' and 'next()'.\n");
+    assert!(stderr.contains("[line 4] Uncaught Error: 5 is not iterable."), "{}", stderr);
+}
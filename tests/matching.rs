@@ -0,0 +1,80 @@
+mod common;
+
+use common::lox;
+
+#[test]
+fn without_a_matching_case_nothing_runs() {
+    let (stdout, stderr, success) = lox(&[], r#"
+        fun check(x) {
+            match (x) {
+                case 1 => print "one";
+                case n if n > 10 => print "large";
+            }
+            print "after " + x;
+        }
+        check(1);
+        check(5);
+    "#);
+    assert!(success, "{}", stderr);
+    assert_eq!(stdout, "one\nafter 1\nafter 5\n");
+}
+
+#[test]
+fn the_first_matching_case_wins_and_bindings_stay_in_it() {
+    let (stdout, stderr, success) = lox(&[], r#"
+        match (1) {
+            case 1 => print "first";
+            case 1 => print "second";
+        }
+        match (5) {
+            case value => print value;
+        }
+        print value;
+    "#);
+    assert!(!success);
+    assert_eq!(stdout, "first\n5\n");
+    assert!(stderr.contains("Undefined variable 'value'."), "{}", stderr);
+}
+
+#[test]
+fn class_patterns_match_subclasses_and_guards_can_reject() {
+    let (stdout, stderr, success) = lox(&[], r#"
+        class Shape {}
+        class Circle < Shape { init(r) { this.r = r; } }
+        fun describe(value) {
+            match (value) {
+                case Circle c if c.r > 5 => return "big circle";
+                case Shape s => return "shape";
+                case "text", 1, nil => return "literal";
+                case _ => return "other";
+            }
+        }
+        print describe(Circle(10));
+        print describe(Circle(1));
+        print describe(nil);
+        print describe(2);
+    "#);
+    assert!(success, "{}", stderr);
+    assert_eq!(stdout, "big circle\nshape\nliteral\nother\n");
+}
+
+#[test]
+fn list_patterns_check_the_length() {
+    let (stdout, stderr, success) = lox(&[], r#"
+        fun command(words) {
+            match (words) {
+                case [] => return "empty";
+                case ["go", direction] => return "go " + direction;
+                case ["take", item, ...rest] => return "take " + item + " " + rest;
+                case _ => return "unknown";
+            }
+        }
+        print command([]);
+        print command(["go", "north"]);
+        print command(["go", "north", "fast"]);
+        print command(["take", "key"]);
+        print command("go");
+    "#);
+    assert!(success, "{}", stderr);
+    assert_eq!(stdout, "empty\ngo north\nunknown\ntake key []\nunknown\n");
+}
@@ -0,0 +1,91 @@
+mod common;
+
+use common::{lox, lox_files};
+
+#[test]
+fn imports_are_resolved_relative_to_the_importing_file() {
+    let (stdout, stderr, success) = lox_files(&[], &[
+        ("main.lox", r#"
+            import { area } from "shapes/square.lox";
+            print area(3);
+        "#),
+        ("shapes/square.lox", r#"
+            import { times } from "math.lox";
+            export fun area(side) { return times(side, side); }
+        "#),
+        ("shapes/math.lox", r#"
+            export fun times(a, b) { return a * b; }
+        "#),
+    ]);
+    assert!(success, "{}", stderr);
+    assert_eq!(stdout, "9\n");
+}
+
+#[test]
+fn modules_imported_twice_run_once() {
+    let (stdout, stderr, success) = lox_files(&[], &[
+        ("main.lox", r#"
+            import "counter.lox";
+            import { count } from "counter.lox";
+            import "user.lox";
+            print count;
+        "#),
+        ("counter.lox", r#"
+            print "loading counter";
+            export var count = 1;
+        "#),
+        ("user.lox", r#"
+            import "counter.lox";
+            print "loading user";
+        "#),
+    ]);
+    assert!(success, "{}", stderr);
+    assert_eq!(stdout, "loading counter\nloading user\n1\n");
+}
+
+#[test]
+fn import_cycles_are_reported() {
+    let (stdout, stderr, success) = lox_files(&[], &[
+        ("a.lox", r#"
+            import "b.lox";
+            print "not reached";
+        "#),
+        ("b.lox", r#"
+            import "a.lox";
+        "#),
+    ]);
+    assert!(!success);
+    assert_eq!(stdout, "");
+    let cycle = stderr.lines()
+        .find_map(|line| line.split_once("Import cycle detected: "))
+        .map(|(_, cycle)| cycle.split(" -> ").collect::<Vec<&str>>())
+        .unwrap_or_else(|| panic!("{}", stderr));
+    assert_eq!(cycle.len(), 3, "{}", stderr);
+    assert!(cycle[0].ends_with("a.lox") && cycle[1].ends_with("b.lox") && cycle[2].ends_with("a.lox."), "{}", stderr);
+}
+
+#[test]
+fn only_exported_names_can_be_imported() {
+    let (stdout, stderr, success) = lox_files(&[], &[
+        ("main.lox", r#"
+            print "started";
+            import { hidden } from "lib.lox";
+        "#),
+        ("lib.lox", r#"
+            export var shown = 1;
+            var hidden = 2;
+        "#),
+    ]);
+    assert!(!success);
+    assert_eq!(stdout, "started\n");
+    assert!(stderr.contains("Module 'lib.lox' does not export 'hidden'."), "{}", stderr);
+}
+
+#[test]
+fn missing_modules_are_reported() {
+    let (_, stderr, success) = lox(&[], r#"
+        import "missing.lox";
+    "#);
+    assert!(!success);
+    assert!(stderr.contains("Could not find module 'missing.lox'."), "{}", stderr);
+}
@@ -0,0 +1,42 @@
+mod common;
+
+use common::lox;
+
+const VECTOR: &str = r#"
+    class V {
+        init(x) { this.x = x; }
+        __add(other) { return V(this.x + other.x); }
+        __neg() { return V(-this.x); }
+        __lt(other) { return this.x < other; }
+        __gt(other) { return this.x > other; }
+    }
+"#;
+
+#[test]
+fn dispatches_operators_to_special_methods() {
+    let (stdout, _, success) = lox(&[], &format!("{}{}", VECTOR, r#"
+        print (V(1) + V(2)).x;
+        print (-V(3)).x;
+        print V(1) < 2;
+    "#));
+    assert!(success);
+    assert_eq!(stdout, "3\n-3\ntrue\n");
+}
+
+#[test]
+fn tries_reflected_comparison_on_right_operand() {
+    let (stdout, _, success) = lox(&[], &format!("{}{}", VECTOR, r#"
+        print 1 < V(2);
+        print 3 < V(2);
+        print 3 > V(2);
+    "#));
+    assert!(success);
+    assert_eq!(stdout, "true\nfalse\ntrue\n");
+}
+
+#[test]
+fn reports_missing_operator_method() {
+    let (_, stderr, success) = lox(&[], &format!("{}{}", VECTOR, "print V(1) * V(2);"));
+    assert!(!success);
+    assert!(stderr.contains("Operator '*' is not supported by V instance: define method '__mul'."), "{}", stderr);
+}
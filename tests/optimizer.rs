@@ -0,0 +1,54 @@
+mod common;
+
+use common::lox;
+
+#[test]
+fn folds_operations_on_literals() {
+    let (stdout, _, success) = lox(&["--dump-ast"], r#"
+        var x = 2;
+        print x * (3 + 4);
+        var s = "a" + 1;
+        print !(1 < 2) or typeof nil;
+    "#);
+    assert!(success);
+    assert_eq!(stdout, "(var x 2)\n(print (* x 7))\n(var s \"a1\")\n(print \"nil\")\n");
+}
+
+#[test]
+fn drops_dead_branches_and_statements() {
+    let (stdout, _, success) = lox(&["--dump-ast"], r#"
+        if (false) print "gone"; else print "kept";
+        while (false) print "never";
+        "unused";
+        print true ? 1 : 2;
+    "#);
+    assert!(success);
+    assert_eq!(stdout, "(print \"kept\")\n(print 1)\n");
+}
+
+#[test]
+fn leaves_failing_operations_to_runtime() {
+    let (stdout, stderr, success) = lox(&[], r#"
+        print "before";
+        print nil + 1;
+    "#);
+    assert!(!success);
+    assert_eq!(stdout, "before\n");
+    assert!(stderr.contains("[line 3] Uncaught Error: Operands must be two numbers or two strings."), "{}", stderr);
+}
+
+#[test]
+fn dumps_private_members_by_their_source_name() {
+    let (stdout, stderr, success) = lox(&["--dump-ast"], r#"
+        class Counter {
+            var #step = 1;
+            increment() {
+                this.#count += this.#step;
+                return this.#read();
+            }
+            #read() { return this.#count; }
+        }
+    "#);
+    assert!(success, "{}", stderr);
+    assert_eq!(stdout, "(class Counter\n  (var #step 1)\n  (fun increment ()\n    (+= (. this #count) (. this #step))\n    (return (call (. this #read))))\n  (fun #read ()\n    (return (. this #count))))\n");
+}
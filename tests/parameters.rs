@@ -0,0 +1,65 @@
+mod common;
+
+use common::lox;
+
+#[test]
+fn missing_arguments_take_their_defaults() {
+    let (stdout, stderr, success) = lox(&[], r#"
+        fun rectangle(width, height = width * 2) { return width * height; }
+        print rectangle(3);
+        print rectangle(3, 4);
+        fun fresh(list = []) {
+            list.push(1);
+            return list;
+        }
+        print fresh();
+        print fresh();
+    "#);
+    assert!(success, "{}", stderr);
+    assert_eq!(stdout, "18\n12\n[1]\n[1]\n");
+}
+
+#[test]
+fn rest_parameters_collect_the_remaining_arguments() {
+    let (stdout, stderr, success) = lox(&[], r#"
+        fun log(level, prefix = ">", ...parts) { print level + prefix + parts; }
+        log("info");
+        log("info", ":");
+        log("info", ":", 1, 2);
+        class Point {
+            init(...coordinates) { this.coordinates = coordinates; }
+        }
+        print Point().coordinates;
+    "#);
+    assert!(success, "{}", stderr);
+    assert_eq!(stdout, "info>[]\ninfo:[]\ninfo:[1, 2]\n[]\n");
+}
+
+#[test]
+fn calls_with_too_few_or_too_many_arguments_fail() {
+    let (stdout, stderr, success) = lox(&[], r#"
+        fun pair(a, b = 1) { return a + b; }
+        fun atLeastOne(first, ...rest) { return first; }
+        try { pair(); } catch (e) { print e.message; }
+        try { pair(1, 2, 3); } catch (e) { print e.message; }
+        try { atLeastOne(); } catch (e) { print e.message; }
+        print atLeastOne(1, 2, 3);
+    "#);
+    assert!(success, "{}", stderr);
+    assert_eq!(stdout, "Expected 1 to 2 arguments but got 0.\nExpected 1 to 2 arguments but got 3.\nExpected at least 1 arguments but got 0.\n1\n");
+}
+
+#[test]
+fn parameter_lists_are_checked_when_parsed() {
+    let (_, stderr, success) = lox(&[], r#"
+        fun wrong(a = 1, b) {}
+    "#);
+    assert!(!success);
+    assert!(stderr.contains("Error at b: Parameters after a default value need a default value too."), "{}", stderr);
+
+    let (_, stderr, success) = lox(&[], r#"
+        fun wrong(...rest, last) {}
+    "#);
+    assert!(!success);
+    assert!(stderr.contains("Rest parameter must be the last parameter."), "{}", stderr);
+}
@@ -0,0 +1,51 @@
+mod common;
+
+use common::lox;
+
+#[test]
+fn private_members_are_rejected_outside_this() {
+    let (stdout, stderr, success) = lox(&[], r#"
+        class Account {
+            init() { this.#balance = 0; }
+        }
+        print "not run";
+        print Account().#balance;
+    "#);
+    assert!(!success);
+    assert_eq!(stdout, "");
+    assert!(stderr.contains("#balance Private members can only be accessed through 'this' inside their class."), "{}", stderr);
+}
+
+#[test]
+fn private_members_of_other_instances_are_rejected() {
+    let (stdout, stderr, success) = lox(&[], r#"
+        class Account {
+            init() { this.#balance = 0; }
+            same(other) { return other.#balance == this.#balance; }
+        }
+    "#);
+    assert!(!success);
+    assert_eq!(stdout, "");
+    assert!(stderr.contains("#balance Private members can only be accessed through 'this' inside their class."), "{}", stderr);
+}
+
+#[test]
+fn subclasses_have_their_own_private_names() {
+    let (stdout, stderr, success) = lox(&[], r#"
+        class Base {
+            init() { this.#name = "base"; }
+            baseName { return this.#name; }
+        }
+        class Derived < Base {
+            init() {
+                super.init();
+                this.#name = "derived";
+            }
+            names() { return this.baseName + " " + this.#name; }
+        }
+        print Derived().names();
+        print fields(Derived());
+    "#);
+    assert!(success, "{}", stderr);
+    assert_eq!(stdout, "base derived\n[]\n");
+}
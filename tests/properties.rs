@@ -0,0 +1,61 @@
+mod common;
+
+use common::lox;
+
+#[test]
+fn getters_and_setters_run_on_property_access() {
+    let (stdout, stderr, success) = lox(&[], r#"
+        class Temperature {
+            init() { this.celsius = 0; }
+            fahrenheit { return this.celsius * 9 / 5 + 32; }
+            fahrenheit=(value) {
+                print "setting " + value;
+                this.celsius = (value - 32) * 5 / 9;
+            }
+        }
+        var temperature = Temperature();
+        temperature.fahrenheit = 212;
+        print temperature.celsius;
+        print temperature.fahrenheit;
+        temperature.fahrenheit += 18;
+        print temperature.celsius;
+    "#);
+    assert!(success, "{}", stderr);
+    assert_eq!(stdout, "setting 212\n100\n212\nsetting 230\n110\n");
+}
+
+#[test]
+fn getters_are_inherited_and_take_precedence_over_fields() {
+    let (stdout, stderr, success) = lox(&[], r#"
+        class Rectangle {
+            init(w, h) {
+                this.w = w;
+                this.h = h;
+            }
+            area { return this.w * this.h; }
+        }
+        class Square < Rectangle {
+            init(size) { super.init(size, size); }
+        }
+        var square = Square(3);
+        print square.area;
+        square.area = 1;
+        print square.area;
+    "#);
+    assert!(success, "{}", stderr);
+    assert_eq!(stdout, "9\n9\n");
+}
+
+#[test]
+fn errors_in_getters_show_the_getter_in_the_stack_trace() {
+    let (stdout, stderr, success) = lox(&[], r#"
+        class Broken {
+            value { return nil + 1; }
+        }
+        print Broken().value;
+    "#);
+    assert!(!success);
+    assert_eq!(stdout, "");
+    assert!(stderr.contains("[line 3] Uncaught Error: Operands must be two numbers or two strings."), "{}", stderr);
+    assert!(stderr.contains("at value() [line 3]\n    at script [line 5]"), "{}", stderr);
+}
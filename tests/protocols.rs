@@ -0,0 +1,66 @@
+mod common;
+
+use common::lox;
+
+#[test]
+fn adding_string_to_instance_without_add_uses_to_string() {
+    let (stdout, _, success) = lox(&[], r#"
+        class P {
+            toString() { return "P"; }
+        }
+        print P() + "!";
+        print "<" + P();
+    "#);
+    assert!(success);
+    assert_eq!(stdout, "P!\n<P\n");
+}
+
+#[test]
+fn equality_with_other_types_is_false_without_calling_eq() {
+    let (stdout, _, success) = lox(&[], r#"
+        class V {
+            init(x) { this.x = x; }
+            __eq(other) { print "eq"; return this.x == other.x; }
+        }
+        print V(1) == nil;
+        print V(1) != 1;
+        print V(1) == V(1);
+    "#);
+    assert!(success);
+    assert_eq!(stdout, "false\ntrue\neq\ntrue\n");
+}
+
+#[test]
+fn bound_methods_equal_by_declaration_and_receiver() {
+    let (stdout, _, success) = lox(&[], r#"
+        class A {
+            foo() {}
+        }
+        var a = A();
+        print a.foo == a.foo;
+        print a.foo == A().foo;
+
+        var keys = Map();
+        keys[a.foo] = "found";
+        print keys[a.foo];
+    "#);
+    assert!(success);
+    assert_eq!(stdout, "true\nfalse\nfound\n");
+}
+
+#[test]
+fn instances_with_equals_and_hash_work_as_map_keys() {
+    let (stdout, _, success) = lox(&[], r#"
+        class Point {
+            init(x, y) { this.x = x; this.y = y; }
+            equals(other) { return this.x == other.x and this.y == other.y; }
+            hash() { return this.x * 31 + this.y; }
+        }
+        var names = Map();
+        names[Point(1, 2)] = "a";
+        print names[Point(1, 2)];
+        print Point(1, 2) == Point(2, 1);
+    "#);
+    assert!(success);
+    assert_eq!(stdout, "a\nfalse\n");
+}
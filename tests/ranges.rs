@@ -0,0 +1,48 @@
+mod common;
+
+use common::lox;
+
+#[test]
+fn ranges_count_lazily_with_a_step() {
+    let (stdout, stderr, success) = lox(&[], r#"
+        for (var i in 3..0 by -1) print i;
+        print (0..10 by 3).length;
+        print (10..0 by -2).length;
+        print (0..10).contains(10);
+        print (0..=10).contains(10);
+        print (0..10 by 3).contains(4);
+        try { print 0..5 by 0; } catch (e) { print e.message; }
+    "#);
+    assert!(success, "{}", stderr);
+    assert_eq!(stdout, "3\n2\n1\n4\n5\nfalse\ntrue\nfalse\nRange step can't be zero.\n");
+}
+
+#[test]
+fn slices_within_bounds() {
+    let (stdout, stderr, success) = lox(&[], r#"
+        var list = [1, 2, 3, 4, 5];
+        print list[1..3];
+        print list[0..=4];
+        print list[0..5];
+        print list[3..1];
+        print list[0..4 by 2];
+        print "hello"[0..=1];
+        print "hello"[5..5];
+    "#);
+    assert!(success, "{}", stderr);
+    assert_eq!(stdout, "[2, 3]\n[1, 2, 3, 4, 5]\n[1, 2, 3, 4, 5]\n[]\n[1, 3]\nhe\n\n");
+}
+
+#[test]
+fn slices_out_of_bounds_raise_an_error() {
+    let (stdout, stderr, success) = lox(&[], r#"
+        var list = [1, 2, 3, 4, 5];
+        try { print list[0..6]; } catch (e) { print e.message; }
+        try { print list[0..=5]; } catch (e) { print e.message; }
+        try { print list[-1..2]; } catch (e) { print e.message; }
+        try { print "abc"[1..9]; } catch (e) { print e.message; }
+        try { print list[0.5..2]; } catch (e) { print e.message; }
+    "#);
+    assert!(success, "{}", stderr);
+    assert_eq!(stdout, "Slice 0..6 out of range.\nSlice 0..=5 out of range.\nSlice -1..2 out of range.\nSlice 1..9 out of range.\nSlice range must contain integers.\n");
+}
@@ -0,0 +1,62 @@
+mod common;
+
+use common::lox;
+
+#[test]
+fn typeof_names_the_type_of_every_value() {
+    let (stdout, stderr, success) = lox(&[], r#"
+        enum Color { Red }
+        class Point {}
+        trait Named {}
+        fun plain() {}
+        fun counter() { yield 1; }
+        var values = [1, "s", true, nil, plain, clock, Point, Point(), [1], Map(), counter(), Named, Color, Color.Red, 0..1];
+        for (var value in values) print typeof value;
+        print typeof typeof 1;
+    "#);
+    assert!(success, "{}", stderr);
+    assert_eq!(stdout, "number\nstring\nbool\nnil\nfunction\nfunction\nclass\ninstance\nlist\nmap\ngenerator\ntrait\nenum\nenum member\nrange\nstring\n");
+}
+
+#[test]
+fn is_checks_classes_superclasses_traits_and_enums() {
+    let (stdout, stderr, success) = lox(&[], r#"
+        trait Named {}
+        class Shape with Named {}
+        class Circle < Shape {}
+        enum Color { Red }
+        var circle = Circle();
+        print circle is Circle;
+        print circle is Shape;
+        print circle is Named;
+        print Shape() is Circle;
+        print "circle" is Shape;
+        print Color.Red is Color;
+    "#);
+    assert!(success, "{}", stderr);
+    assert_eq!(stdout, "true\ntrue\ntrue\nfalse\nfalse\ntrue\n");
+}
+
+#[test]
+fn is_requires_a_class_trait_or_enum() {
+    let (_, stderr, success) = lox(&[], r#"
+        print 1 is 2;
+    "#);
+    assert!(!success);
+    assert!(stderr.contains("[line 2] Uncaught Error: Right operand of 'is' must be a class, trait or enum."), "{}", stderr);
+}
+
+#[test]
+fn methods_and_fields_list_names() {
+    let (stdout, stderr, success) = lox(&[], r#"
+        class Point {
+            init(x) { this.x = x; }
+            norm() { return this.x; }
+        }
+        print methods(Point);
+        print fields(Point(1));
+        try { fields(1); } catch (e) { print e.message; }
+    "#);
+    assert!(success, "{}", stderr);
+    assert_eq!(stdout, "[init, norm]\n[x]\nNative function [fields]: Expecting instance argument.\n");
+}
@@ -0,0 +1,43 @@
+mod common;
+
+use common::lox;
+
+#[test]
+fn reports_undefined_globals_and_arity_mismatches() {
+    let (stdout, stderr, success) = lox(&["--strict"], r#"
+        print "not run";
+        print undefinedName;
+        fun two(a, b) {}
+        two(1);
+        class Point { init(x, y) {} }
+        Point(1, 2, 3);
+    "#);
+    assert!(!success);
+    assert_eq!(stdout, "");
+    assert!(stderr.contains("Undefined variable 'undefinedName'."), "{}", stderr);
+    assert!(stderr.contains("Expected 2 arguments but got 1."), "{}", stderr);
+    assert!(stderr.contains("Expected 2 arguments but got 3."), "{}", stderr);
+}
+
+#[test]
+fn accepts_globals_declared_later_and_natives() {
+    let (stdout, stderr, success) = lox(&["--strict"], r#"
+        fun greet() { return greeting; }
+        var greeting = "hi";
+        print greet();
+        print typeof clock();
+    "#);
+    assert!(success, "{}", stderr);
+    assert_eq!(stdout, "hi\nnumber\n");
+}
+
+#[test]
+fn without_strict_undefined_globals_fail_at_runtime() {
+    let (stdout, stderr, success) = lox(&[], r#"
+        print "run";
+        print undefinedName;
+    "#);
+    assert!(!success);
+    assert_eq!(stdout, "run\n");
+    assert!(stderr.contains("Undefined variable 'undefinedName'."), "{}", stderr);
+}
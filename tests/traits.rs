@@ -0,0 +1,60 @@
+mod common;
+
+use common::lox;
+
+#[test]
+fn classes_get_trait_members_and_can_override_them() {
+    let (stdout, stderr, success) = lox(&[], r#"
+        trait Greeter {
+            greet() { return "hello " + this.name; }
+            shout { return this.greet() + "!"; }
+        }
+        class Person with Greeter {
+            init(name) { this.name = name; }
+        }
+        class Robot with Greeter {
+            greet() { return "beep"; }
+        }
+        print Person("ada").shout;
+        print Robot().greet();
+        print Person("ada") is Greeter;
+    "#);
+    assert!(success, "{}", stderr);
+    assert_eq!(stdout, "hello ada!\nbeep\ntrue\n");
+}
+
+#[test]
+fn trait_members_override_inherited_ones() {
+    let (stdout, stderr, success) = lox(&[], r#"
+        trait Loud { name() { return "trait"; } }
+        class Base { name() { return "base"; } }
+        class Child < Base with Loud {}
+        print Child().name();
+    "#);
+    assert!(success, "{}", stderr);
+    assert_eq!(stdout, "trait\n");
+}
+
+#[test]
+fn conflicting_trait_members_must_be_resolved_by_the_class() {
+    let (stdout, stderr, success) = lox(&[], r#"
+        trait A { hello() { return "A"; } }
+        trait B { hello() { return "B"; } }
+        class Resolved with A, B { hello() { return "own"; } }
+        print Resolved().hello();
+        class Conflict with A, B {}
+    "#);
+    assert!(!success);
+    assert_eq!(stdout, "own\n");
+    assert!(stderr.contains("[line 6] Uncaught Error: Class 'Conflict' gets 'hello' from both trait 'A' and trait 'B', it must define 'hello' itself."), "{}", stderr);
+}
+
+#[test]
+fn only_traits_can_be_used() {
+    let (_, stderr, success) = lox(&[], r#"
+        class Plain {}
+        class Wrong with Plain {}
+    "#);
+    assert!(!success);
+    assert!(stderr.contains("'Plain' is not a trait."), "{}", stderr);
+}